@@ -1,20 +1,22 @@
+use crate::parse::{parse_num, ParseError};
+
 #[aoc(day1, part1)]
-fn part1(input: &str) -> i32 {
-    return *collect_cals(input).iter().max().unwrap();
+fn part1(input: &str) -> Result<i32, ParseError> {
+    return Ok(*collect_cals(input)?.iter().max().unwrap());
 }
 
 #[aoc(day1, part2)]
-fn part2(input: &str) -> i32 {
-    let mut all_cal: Vec<i32> = collect_cals(input);
+fn part2(input: &str) -> Result<i32, ParseError> {
+    let mut all_cal: Vec<i32> = collect_cals(input)?;
 
     all_cal.sort_by(|a, b| b.cmp(a));
 
-    return all_cal[0] + all_cal[1] + all_cal[2];
+    return Ok(all_cal[0] + all_cal[1] + all_cal[2]);
 }
 
-fn collect_cals(input: &str) -> Vec<i32> {
+fn collect_cals(input: &str) -> Result<Vec<i32>, ParseError> {
     return input
         .split("\n\n")
-        .map(|elf| elf.lines().map(|l| l.parse::<i32>().unwrap()).sum())
+        .map(|elf| elf.lines().map(|l| parse_num::<i32>(1, input, l)).sum())
         .collect();
 }
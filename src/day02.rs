@@ -1,3 +1,5 @@
+use crate::parse::ParseError;
+
 pub struct Round1 {
     opponent: Shape,
     me: Shape,
@@ -63,68 +65,76 @@ const WIN: i32 = 6;
 const DRAW: i32 = 3;
 const LOSE: i32 = 0;
 
-pub fn read_input_part1(input: &str) -> Vec<Round1> {
+pub fn read_input_part1(input: &str) -> Result<Vec<Round1>, ParseError> {
     return input
         .lines()
         .map(|l| {
-            let mut inp = l.trim().split(' ').map(|d| d.parse().unwrap());
-            Round1 {
-                opponent: map_opp(inp.next().unwrap()),
-                me: map_me(inp.next().unwrap()),
-            }
+            let (opp, me) = split_round(input, l)?;
+            Ok(Round1 {
+                opponent: map_opp(input, opp)?,
+                me: map_me(input, me)?,
+            })
         })
         .collect();
 
-    fn map_me(c: char) -> Shape {
-        match c {
-            'X' => Shape::Rock,
-            'Y' => Shape::Paper,
-            'Z' => Shape::Scissors,
-            _ => panic!("unkown shape {}", c),
+    fn map_me(input: &str, s: &str) -> Result<Shape, ParseError> {
+        match s {
+            "X" => Ok(Shape::Rock),
+            "Y" => Ok(Shape::Paper),
+            "Z" => Ok(Shape::Scissors),
+            _ => Err(ParseError::new(2, input, s, "unkown shape")),
         }
     }
 }
 
-pub fn read_input_part2(input: &str) -> Vec<Round2> {
+pub fn read_input_part2(input: &str) -> Result<Vec<Round2>, ParseError> {
     return input
         .lines()
         .map(|l| {
-            let mut inp = l.trim().split(' ').map(|d| d.parse().unwrap());
-            Round2 {
-                opponent: map_opp(inp.next().unwrap()),
-                outcome: map_outcome(inp.next().unwrap()),
-            }
+            let (opp, outcome) = split_round(input, l)?;
+            Ok(Round2 {
+                opponent: map_opp(input, opp)?,
+                outcome: map_outcome(input, outcome)?,
+            })
         })
         .collect();
 
-    fn map_outcome(c: char) -> Outcome {
-        match c {
-            'X' => Outcome::Lose,
-            'Y' => Outcome::Draw,
-            'Z' => Outcome::Win,
-            _ => panic!("unkown Outcome {}", c),
+    fn map_outcome(input: &str, s: &str) -> Result<Outcome, ParseError> {
+        match s {
+            "X" => Ok(Outcome::Lose),
+            "Y" => Ok(Outcome::Draw),
+            "Z" => Ok(Outcome::Win),
+            _ => Err(ParseError::new(2, input, s, "unkown Outcome")),
         }
     }
 }
 
+fn split_round<'a>(input: &str, line: &'a str) -> Result<(&'a str, &'a str), ParseError> {
+    let mut words = line.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some(opp), Some(me)) => Ok((opp, me)),
+        _ => Err(ParseError::missing(2, input, line, "second column")),
+    }
+}
+
 #[aoc(day2, part1)]
-fn part1(input: &str) -> i32 {
-    read_input_part1(input)
+fn part1(input: &str) -> Result<i32, ParseError> {
+    Ok(read_input_part1(input)?
         .iter()
         .map(|r| r.me.score() + match_points(&r.opponent, &r.me))
-        .sum()
+        .sum())
 }
 
 #[aoc(day2, part2)]
-fn part2(input: &str) -> i32 {
-    read_input_part2(input)
+fn part2(input: &str) -> Result<i32, ParseError> {
+    Ok(read_input_part2(input)?
         .iter()
         .map(|r| match r.outcome {
             Outcome::Lose => 0 + r.opponent.beats().score(),
             Outcome::Draw => 3 + r.opponent.score(),
             Outcome::Win => 6 + r.opponent.loses_to().score(),
         })
-        .sum()
+        .sum())
 }
 
 fn match_points(opp: &Shape, me: &Shape) -> i32 {
@@ -137,12 +147,12 @@ fn match_points(opp: &Shape, me: &Shape) -> i32 {
     }
 }
 
-fn map_opp(c: char) -> Shape {
-    match c {
-        'A' => Shape::Rock,
-        'B' => Shape::Paper,
-        'C' => Shape::Scissors,
-        _ => panic!("unkown shape {}", c),
+fn map_opp(input: &str, s: &str) -> Result<Shape, ParseError> {
+    match s {
+        "A" => Ok(Shape::Rock),
+        "B" => Ok(Shape::Paper),
+        "C" => Ok(Shape::Scissors),
+        _ => Err(ParseError::new(2, input, s, "unkown shape")),
     }
 }
 
@@ -158,13 +168,21 @@ C Z";
     fn example_part1() {
         let result = part1(INPUT);
 
-        assert_eq!(result, 15)
+        assert_eq!(result, Ok(15))
     }
 
     #[test]
     fn example_part2() {
         let result = part2(INPUT);
 
-        assert_eq!(result, 12)
+        assert_eq!(result, Ok(12))
+    }
+
+    #[test]
+    fn test_unknown_shape() {
+        let e = part1("A Y\nB Q").unwrap_err();
+
+        assert_eq!((e.line, e.column), (2, 3));
+        assert_eq!(e.text, "Q");
     }
 }
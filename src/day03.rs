@@ -1,47 +1,68 @@
+use crate::parse::ParseError;
+
 #[aoc(day3, part1)]
-fn part1(input: &str) -> u32 {
+fn part1(input: &str) -> Result<u32, ParseError> {
     input
         .lines()
-        .map(|l| find_common(&l[0..l.len() / 2], &l[l.len() / 2..l.len()]))
-        .map(|c| prio(c))
+        .map(|l| {
+            let items = read_rucksack(input, l)?;
+            let (p1, p2) = items.split_at(items.len() / 2);
+            find_common(p1, p2)
+                .map(prio)
+                .ok_or_else(|| ParseError::new(3, input, l, "no common item"))
+        })
         .sum()
 }
 
 #[aoc(day3, part2)]
-fn part2(input: &str) -> u32 {
-    let mut lines = input.lines().peekable();
-    let mut sum = 0;
-
-    while lines.peek().is_some() {
-        let common = find_common3(
-            lines.next().unwrap(),
-            lines.next().unwrap(),
-            lines.next().unwrap(),
-        );
-        sum += prio(common);
-    }
+fn part2(input: &str) -> Result<u32, ParseError> {
+    let lines: Vec<&str> = input.lines().collect();
 
-    sum
+    lines
+        .chunks(3)
+        .map(|group| {
+            let last = group[group.len() - 1];
+            if group.len() < 3 {
+                return Err(ParseError::missing(3, input, last, "rucksack of the group"));
+            }
+            for l in group {
+                read_rucksack(input, l)?;
+            }
+            find_common3(group[0], group[1], group[2])
+                .map(prio)
+                .ok_or_else(|| ParseError::new(3, input, last, "no common item"))
+        })
+        .sum()
+}
+
+// only letters are items
+fn read_rucksack<'a>(input: &str, line: &'a str) -> Result<&'a str, ParseError> {
+    match line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        Some((i, c)) => Err(ParseError::new(
+            3,
+            input,
+            &line[i..i + c.len_utf8()],
+            "not a letter",
+        )),
+        None => Ok(line),
+    }
 }
 
 fn prio(c: char) -> u32 {
     match c {
         'a'..='z' => c as u32 - 96,
         'A'..='Z' => c as u32 - 64 + 26,
-        _ => panic!("Not a letter {}", c),
+        _ => unreachable!("items are checked by read_rucksack"),
     }
 }
 
-fn find_common(p1: &str, p2: &str) -> char {
-    p1.chars()
-        .find(|c| p2.contains(|t| t == *c))
-        .expect("No common letter found")
+fn find_common(p1: &str, p2: &str) -> Option<char> {
+    p1.chars().find(|c| p2.contains(|t| t == *c))
 }
 
-fn find_common3(p1: &str, p2: &str, p3: &str) -> char {
+fn find_common3(p1: &str, p2: &str, p3: &str) -> Option<char> {
     p1.chars()
         .find(|c| p2.contains(|t| t == *c) && p3.contains(|t| t == *c))
-        .expect("No common letter found")
 }
 
 #[cfg(test)]
@@ -57,12 +78,12 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 157)
+        assert_eq!(part1(EXAMPLE), Ok(157))
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 70)
+        assert_eq!(part2(EXAMPLE), Ok(70))
     }
 
     #[test]
@@ -75,8 +96,24 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_find_common() {
-        assert_eq!(find_common("vJrwpWtwJgWr", "hcsFMMfFFhFp"), 'p');
-        assert_eq!(find_common("jqHRNqRjqzjGDLGL", "rsFMfFZSrLrFZsSL"), 'L');
-        assert_eq!(find_common("PmmdzqPrV", "vPwwTWBwg"), 'P');
+        assert_eq!(find_common("vJrwpWtwJgWr", "hcsFMMfFFhFp"), Some('p'));
+        assert_eq!(
+            find_common("jqHRNqRjqzjGDLGL", "rsFMfFZSrLrFZsSL"),
+            Some('L')
+        );
+        assert_eq!(find_common("PmmdzqPrV", "vPwwTWBwg"), Some('P'));
+    }
+
+    #[test]
+    fn test_read_error() {
+        let e = part1("vJrwpWtwJgWr\nab1d").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 3));
+        assert_eq!(e.msg, "not a letter");
+
+        assert_eq!(part1("abcd").unwrap_err().msg, "no common item");
+
+        let e = part2(&EXAMPLE[..EXAMPLE.rfind('\n').unwrap()]).unwrap_err();
+        assert_eq!(e.line, 5);
+        assert_eq!(e.msg, "missing rucksack of the group");
     }
 }
@@ -1,5 +1,7 @@
 use std::cmp;
 
+use crate::parse::{parse_num, ParseError};
+
 #[derive(Debug)]
 pub struct Work {
     e1: Intervall,
@@ -23,11 +25,12 @@ pub struct Intervall {
 }
 
 impl Intervall {
-    fn new(start: i32, end: i32) -> Self {
+    fn new(start: i32, end: i32) -> Result<Self, ParseError> {
         if end < start {
-            panic!("unorderd input")
+            let text = format!("{}-{}", start, end);
+            return Err(ParseError::new(4, &text, &text, "unorderd input"));
         }
-        Self { start, end }
+        Ok(Self { start, end })
     }
 
     fn size(&self) -> i32 {
@@ -49,15 +52,21 @@ impl Intervall {
 }
 
 #[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Vec<Work> {
+pub fn input_generator(input: &str) -> Result<Vec<Work>, ParseError> {
     input
         .lines()
         .map(|l| {
             let mut ranges = l.trim().split(',').map(|r| {
-                let mut nums = r.split('-').map(|n| n.parse().unwrap());
-                Intervall::new(nums.next().unwrap(), nums.next().unwrap())
+                let (start, end) = r
+                    .split_once('-')
+                    .ok_or_else(|| ParseError::new(4, input, r, "expected a range"))?;
+                Intervall::new(parse_num(4, input, start)?, parse_num(4, input, end)?)
+                    .map_err(|e| e.within(input, r))
             });
-            Work::new(ranges.next().unwrap(), ranges.next().unwrap())
+            match (ranges.next(), ranges.next()) {
+                (Some(e1), Some(e2)) => Ok(Work::new(e1?, e2?)),
+                _ => Err(ParseError::missing(4, input, l, "second range")),
+            }
         })
         .collect()
 }
@@ -95,30 +104,48 @@ mod test {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&input_generator(EXAMPLE)), 2)
+        assert_eq!(part1(&input_generator(EXAMPLE).unwrap()), 2)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&input_generator(EXAMPLE)), 4)
+        assert_eq!(part2(&input_generator(EXAMPLE).unwrap()), 4)
     }
 
     #[test]
     fn test_size() {
-        assert_eq!(Intervall::new(0, 0).size(), 0);
-        assert_eq!(Intervall::new(5, 15).size(), 10);
+        assert_eq!(Intervall::new(0, 0).unwrap().size(), 0);
+        assert_eq!(Intervall::new(5, 15).unwrap().size(), 10);
     }
 
     #[test]
     fn test_inter() {
-        assert_eq!(Intervall::new(0, 0).inter(&Intervall::new(1, 1)), None);
         assert_eq!(
-            Intervall::new(0, 5).inter(&Intervall::new(1, 1)),
-            Some(Intervall::new(1, 1))
+            Intervall::new(0, 0)
+                .unwrap()
+                .inter(&Intervall::new(1, 1).unwrap()),
+            None
+        );
+        assert_eq!(
+            Intervall::new(0, 5)
+                .unwrap()
+                .inter(&Intervall::new(1, 1).unwrap()),
+            Some(Intervall::new(1, 1).unwrap())
         );
         assert_eq!(
-            Intervall::new(0, 5).inter(&Intervall::new(3, 7)),
-            Some(Intervall::new(3, 5))
+            Intervall::new(0, 5)
+                .unwrap()
+                .inter(&Intervall::new(3, 7).unwrap()),
+            Some(Intervall::new(3, 5).unwrap())
         );
     }
+
+    #[test]
+    fn test_unordered_range() {
+        let e = input_generator("2-4,6-8\n2-3,5-4").unwrap_err();
+
+        assert_eq!((e.line, e.column), (2, 5));
+        assert_eq!(e.text, "5-4");
+        assert_eq!(e.msg, "unorderd input");
+    }
 }
@@ -1,14 +1,19 @@
 use regex::Regex;
 
+use crate::parse::{parse_num, ParseError};
+
 pub struct Store {
     stacks: Vec<Vec<char>>,
 }
 
 impl Store {
-    fn new(input: &str) -> Store {
+    fn new(input: &str) -> Result<Store, ParseError> {
         let mut lines: Vec<&str> = input.lines().collect();
         // every stack takes 4 chars the last 3
-        let num_stacks = (lines.last().unwrap().len() / 4) + 1;
+        let num_stacks = match lines.last() {
+            Some(l) => (l.len() / 4) + 1,
+            None => return Err(ParseError::missing(5, input, input, "stack numbers")),
+        };
         let mut stacks: Vec<Vec<char>> = vec![Vec::new(); num_stacks];
         // discard counting line
         lines.pop();
@@ -23,12 +28,12 @@ impl Store {
             }
         }
 
-        Store { stacks }
+        Ok(Store { stacks })
     }
 
     fn apply(&mut self, instr: Instr) {
         for _ in 0..instr.amount {
-            //println!("{:?}\n step: {:?}", self.stacks, instr);
+            trace!(5, "{:?} step: {:?}", self.stacks, instr);
 
             let id = self
                 .stacks
@@ -48,7 +53,7 @@ impl Store {
         let mut moving = Vec::new();
 
         for _ in 0..instr.amount {
-            //println!("{:?}\n step: {:?}", self.stacks, instr);
+            trace!(5, "{:?} step: {:?}", self.stacks, instr);
 
             let id = self
                 .stacks
@@ -81,51 +86,66 @@ pub struct Instr {
 }
 
 lazy_static::lazy_static! {
-    static ref MOVE_REGEX: Regex = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
+    static ref MOVE_REGEX: Regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
 }
 impl Instr {
-    fn new(input: &str) -> Instr {
-        let caps = MOVE_REGEX.captures(input).unwrap();
+    // stacks are numbered from 1 to num_stacks
+    fn new(input: &str, num_stacks: usize) -> Result<Instr, ParseError> {
+        let caps = MOVE_REGEX
+            .captures(input)
+            .ok_or_else(|| ParseError::new(5, input, input, "expected a move"))?;
+        let part = |i| &input[caps.get(i).unwrap().range()];
+        let stack = |i| {
+            let n = parse_num(5, input, part(i))?;
+            if (1..=num_stacks).contains(&n) {
+                Ok(n)
+            } else {
+                Err(ParseError::new(5, input, part(i), "no such stack"))
+            }
+        };
 
-        Instr {
-            amount: caps.get(1).map(|s| s.as_str().parse().unwrap()).unwrap(),
-            from: caps.get(2).map(|s| s.as_str().parse().unwrap()).unwrap(),
-            to: caps.get(3).map(|s| s.as_str().parse().unwrap()).unwrap(),
-        }
+        Ok(Instr {
+            amount: parse_num(5, input, part(1))?,
+            from: stack(2)?,
+            to: stack(3)?,
+        })
     }
 }
 
-pub fn load_data(input: &str) -> (Store, Vec<Instr>) {
-    let mut parts = input.split("\n\n");
-    let store_str = parts.next().unwrap();
-    let intr_str = parts.next().unwrap();
+pub fn load_data(input: &str) -> Result<(Store, Vec<Instr>), ParseError> {
+    let (store_str, intr_str) = input
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::missing(5, input, input, "empty line before the moves"))?;
 
-    let store = Store::new(store_str);
-    let prog = intr_str.lines().map(|l| Instr::new(l)).collect();
+    let store = Store::new(store_str).map_err(|e| e.within(input, store_str))?;
+    let prog = intr_str
+        .lines()
+        .map(|l| Instr::new(l, store.stacks.len()).map_err(|e| e.within(input, l)))
+        .collect::<Result<_, _>>()?;
 
-    (store, prog)
+    Ok((store, prog))
 }
 
 #[aoc(day5, part1)]
-fn part1(input: &str) -> String {
-    let (mut store, prog) = load_data(input);
+fn part1(input: &str) -> Result<String, ParseError> {
+    let (mut store, prog) = load_data(input)?;
 
     for instr in prog {
         store.apply(instr);
     }
 
-    store.read_top()
+    Ok(store.read_top())
 }
 
 #[aoc(day5, part2)]
-fn part2(input: &str) -> String {
-    let (mut store, prog) = load_data(input);
+fn part2(input: &str) -> Result<String, ParseError> {
+    let (mut store, prog) = load_data(input)?;
 
     for instr in prog {
         store.apply_9001(instr);
     }
 
-    store.read_top()
+    Ok(store.read_top())
 }
 
 #[cfg(test)]
@@ -144,17 +164,17 @@ move 1 from 1 to 2";
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), "CMZ")
+        assert_eq!(part1(EXAMPLE).unwrap(), "CMZ")
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), "MCD")
+        assert_eq!(part2(EXAMPLE).unwrap(), "MCD")
     }
 
     #[test]
     fn test_load_data_store() {
-        let (store, _) = load_data(EXAMPLE);
+        let (store, _) = load_data(EXAMPLE).unwrap();
 
         assert_eq!(store.stacks.len(), 3);
         assert_eq!(
@@ -165,7 +185,7 @@ move 1 from 1 to 2";
 
     #[test]
     fn test_load_data_prog() {
-        let (_, prog) = load_data(EXAMPLE);
+        let (_, prog) = load_data(EXAMPLE).unwrap();
 
         assert_eq!(prog.len(), 4);
         assert_eq!(
@@ -194,4 +214,26 @@ move 1 from 1 to 2";
             ]
         )
     }
+
+    #[test]
+    fn test_bad_move() {
+        let input = EXAMPLE.replace("move 2 from 2 to 1", "move 2 from two to 1");
+        let e = load_data(&input).err().unwrap();
+
+        assert_eq!((e.line, e.column), (8, 1));
+        assert_eq!(e.text, "move 2 from two to 1");
+
+        let input = EXAMPLE.replace("move 2 from 2 to 1", "move 2 from 0 to 1");
+        let e = load_data(&input).err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (8, 13, "0"));
+        assert_eq!(e.msg, "no such stack");
+
+        let input = EXAMPLE.replace("move 1 from 1 to 2", "move 1 from 1 to 4");
+        let e = load_data(&input).err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (9, 18, "4"));
+
+        let input = EXAMPLE.replace("move 3 from 1 to 3", "move 3 from 1 to 3xyz");
+        let e = load_data(&input).err().unwrap();
+        assert_eq!((e.line, e.msg.as_str()), (7, "expected a move"));
+    }
 }
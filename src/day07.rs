@@ -1,13 +1,15 @@
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 
+use crate::parse::{parse_num, ParseError};
+
 #[derive(Debug, PartialEq)]
 struct Filesystem<'a> {
     files: BTreeMap<Vec<&'a str>, File>,
 }
 
 impl<'a> Filesystem<'a> {
-    fn new(input: &'a str) -> Self {
+    fn new(input: &'a str) -> Result<Self, ParseError> {
         let mut lines = input.lines().peekable();
 
         let mut fs = Filesystem {
@@ -15,8 +17,8 @@ impl<'a> Filesystem<'a> {
         };
         let mut cwd_path: Vec<&str> = vec![];
 
-        while lines.peek().is_some() {
-            let cmd = Command::new(lines.next().unwrap());
+        while let Some(line) = lines.next() {
+            let cmd = Command::new(line).map_err(|e| e.within(input, line))?;
             match cmd {
                 Command::Cd(p) => match p {
                     "/" => cwd_path = vec![],
@@ -30,9 +32,12 @@ impl<'a> Filesystem<'a> {
                         let line = lines.next().unwrap();
                         if !line.starts_with("dir") {
                             let mut words = line.split_whitespace();
-                            let size: usize = words.next().unwrap().parse().unwrap();
+                            let (size, name) = match (words.next(), words.next()) {
+                                (Some(size), Some(name)) => (parse_num(7, input, size)?, name),
+                                _ => return Err(ParseError::missing(7, input, line, "file name")),
+                            };
                             let mut fp = cwd_path.clone();
-                            fp.push(words.next().unwrap());
+                            fp.push(name);
                             fs.files.insert(fp, File::new(size));
                         }
                     }
@@ -40,7 +45,7 @@ impl<'a> Filesystem<'a> {
             }
         }
 
-        fs
+        Ok(fs)
     }
 
     fn get_folder_sizes(&self) -> Vec<(String, usize)> {
@@ -86,44 +91,49 @@ enum Command<'a> {
 }
 
 impl<'a> Command<'a> {
-    fn new(line: &str) -> Command {
+    fn new(line: &'a str) -> Result<Command<'a>, ParseError> {
         if !line.starts_with("$") {
-            panic!("Line is not a command");
+            return Err(ParseError::new(7, line, line, "Line is not a command"));
         }
 
         let mut words = line.split_whitespace().skip(1);
-        match words.next().unwrap() {
-            "cd" => Command::Cd(words.next().unwrap()),
-            "ls" => Command::Ls,
-            _ => panic!("unknown command"),
+        match words.next() {
+            Some("cd") => match words.next() {
+                Some(p) => Ok(Command::Cd(p)),
+                None => Err(ParseError::missing(7, line, line, "folder")),
+            },
+            Some("ls") => Ok(Command::Ls),
+            Some(cmd) => Err(ParseError::new(7, line, cmd, "unknown command")),
+            None => Err(ParseError::missing(7, line, line, "command")),
         }
     }
 }
 
 #[aoc(day7, part1)]
-fn part1(input: &str) -> usize {
-    let fs = Filesystem::new(input);
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let fs = Filesystem::new(input)?;
 
-    fs.get_folder_sizes()
+    Ok(fs
+        .get_folder_sizes()
         .iter()
         .map(|(_, s)| s)
         .filter(|s| **s < 100000)
-        .sum()
+        .sum())
 }
 
 #[aoc(day7, part2)]
-fn part2(input: &str) -> usize {
-    let fs = Filesystem::new(input);
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let fs = Filesystem::new(input)?;
     let folder_sizes = fs.get_folder_sizes();
     let used_space: usize = fs.files.values().map(|f| f.size).sum();
 
     let min_delete = used_space - (70000000 - 30000000);
-    folder_sizes
+    Ok(folder_sizes
         .iter()
         .map(|(_, s)| *s)
         .filter(|s| *s >= min_delete)
         .min()
-        .unwrap()
+        .unwrap())
 }
 
 #[cfg(test)]
@@ -156,17 +166,17 @@ $ ls
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 95437)
+        assert_eq!(part1(EXAMPLE), Ok(95437))
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 24933642)
+        assert_eq!(part2(EXAMPLE), Ok(24933642))
     }
 
     #[test]
     fn test_read_fs() {
-        let fs = Filesystem::new(EXAMPLE);
+        let fs = Filesystem::new(EXAMPLE).unwrap();
 
         let exp = Filesystem {
             files: {
@@ -189,11 +199,19 @@ $ ls
     }
     #[test]
     fn test_fs_dirsize() {
-        let fs = Filesystem::new(EXAMPLE);
+        let fs = Filesystem::new(EXAMPLE).unwrap();
 
         assert!(fs.get_folder_sizes().contains(&("a/e".to_string(), 584)));
         assert!(fs
             .get_folder_sizes()
             .contains(&("d".to_string(), 4060174 + 8033020 + 5626152 + 7214296)));
     }
+
+    #[test]
+    fn test_unknown_command() {
+        let e = Filesystem::new("$ cd /\n$ ls\n12 a.txt\n$ rm a.txt").unwrap_err();
+
+        assert_eq!((e.line, e.column), (4, 3));
+        assert_eq!(e.text, "rm");
+    }
 }
@@ -1,54 +1,37 @@
-fn parse(input: &str) -> Vec<Vec<u32>> {
-    let mut vert = Vec::new();
-
-    for line in input.lines() {
-        let mut row = Vec::new();
-        for c in line.chars() {
-            row.push(c.to_digit(10).unwrap());
-        }
-        vert.push(row);
-    }
-
-    vert
+use crate::{
+    geometry::{Direction, Direction::*, Point},
+    grid::Grid,
+    parse::ParseError,
+};
+
+fn parse(input: &str) -> Result<Grid<u32>, ParseError> {
+    Grid::parse(8, input, |c| c.to_digit(10).ok_or("expected a digit"))
 }
 
-fn check_visible(map: &Vec<Vec<u32>>) -> Vec<Vec<bool>> {
-    let height = map.len();
-    let width = map.first().unwrap().len();
-    let mut vis = vec![vec![false; height]; width];
+fn check_visible(map: &Grid<u32>) -> Grid<bool> {
+    let mut vis = Grid::new(map.width(), map.height(), false);
+    let max = map.bounds().max;
 
     // lr
-    for row in 0..height {
-        set_by_sightline(&map, &mut vis, Iter2d::new((row, 0), (0, 1), (0, width)));
-        set_by_sightline(
-            &map,
-            &mut vis,
-            Iter2d::new((row, width - 1), (0, -1), (0, width)),
-        );
+    for y in 0..=max.1 {
+        set_by_sightline(map, &mut vis, map.ray(Point(0, y), R));
+        set_by_sightline(map, &mut vis, map.ray(Point(max.0, y), L));
     }
     // ud
-    for column in 0..width {
-        set_by_sightline(
-            &map,
-            &mut vis,
-            Iter2d::new((0, column), (1, 0), (0, height)),
-        );
-        set_by_sightline(
-            &map,
-            &mut vis,
-            Iter2d::new((height - 1, column), (-1, 0), (0, height)),
-        );
+    for x in 0..=max.0 {
+        set_by_sightline(map, &mut vis, map.ray(Point(x, 0), D));
+        set_by_sightline(map, &mut vis, map.ray(Point(x, max.1), U));
     }
 
     vis
 }
 
-fn set_by_sightline(map: &Vec<Vec<u32>>, vis: &mut Vec<Vec<bool>>, iter: Iter2d) {
+fn set_by_sightline(map: &Grid<u32>, vis: &mut Grid<bool>, ray: impl Iterator<Item = Point>) {
     let mut max = None;
-    for (i, j) in iter {
-        let h = get_height(map, i, j);
+    for pos in ray {
+        let h = map[pos];
         if max.is_none() || h > max.unwrap() {
-            set_vis(vis, i, j);
+            vis[pos] = true;
             max = Some(h);
             if max == Some(9) {
                 break;
@@ -57,86 +40,19 @@ fn set_by_sightline(map: &Vec<Vec<u32>>, vis: &mut Vec<Vec<bool>>, iter: Iter2d)
     }
 }
 
-fn get_height(map: &Vec<Vec<u32>>, h: usize, r: usize) -> u32 {
-    *map.get(h).unwrap().get(r).unwrap()
-}
-fn set_vis(vis: &mut Vec<Vec<bool>>, h: usize, r: usize) {
-    *vis.get_mut(h).unwrap().get_mut(r).unwrap() = true;
-}
-
-struct Iter2d {
-    pos: (usize, usize),
-    dir: (i32, i32),
-    range: (usize, usize),
-    overflow: bool,
-}
-
-impl Iter2d {
-    fn new(pos: (usize, usize), dir: (i32, i32), range: (usize, usize)) -> Self {
-        Self {
-            pos,
-            dir,
-            range,
-            overflow: false,
-        }
-    }
-}
-
-impl Iterator for Iter2d {
-    type Item = (usize, usize);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.overflow {
-            return None;
-        }
-
-        let curr = self.pos;
-        match add(self.pos.0, self.dir.0) {
-            Some(v) => self.pos.0 = v,
-            None => self.overflow = true,
-        }
-        match add(self.pos.1, self.dir.1) {
-            Some(v) => self.pos.1 = v,
-            None => self.overflow = true,
-        }
-
-        if std::cmp::min(self.pos.0, self.pos.1) < self.range.0
-            || std::cmp::max(self.pos.0, self.pos.1) > self.range.1
-        {
-            None
-        } else {
-            Some(curr)
-        }
-    }
-}
-
-fn add(u: usize, i: i32) -> Option<usize> {
-    if i.is_negative() {
-        u.checked_sub(i.wrapping_abs() as u32 as usize)
-    } else {
-        u.checked_add(i as usize)
-    }
-}
-
-fn score_view(map: &Vec<Vec<u32>>, i: usize, j: usize) -> u32 {
-    let height = map.len();
-    let width = map.first().unwrap().len();
-    let n = sightline_length(&map, &mut Iter2d::new((i, j), (-1, 0), (0, height)));
-    let e = sightline_length(&map, &mut Iter2d::new((i, j), (0, 1), (0, width)));
-    let s = sightline_length(&map, &mut Iter2d::new((i, j), (1, 0), (0, height)));
-    let w = sightline_length(&map, &mut Iter2d::new((i, j), (0, -1), (0, width)));
-
-    n * e * s * w
+fn score_view(map: &Grid<u32>, pos: Point) -> u32 {
+    Direction::ALL
+        .iter()
+        .map(|dir| sightline_length(map, map.ray(pos, *dir)))
+        .product()
 }
 
-fn sightline_length(map: &Vec<Vec<u32>>, iter: &mut Iter2d) -> u32 {
-    let (start_i, start_j) = iter.next().unwrap();
-    let house_height = get_height(map, start_i, start_j);
+fn sightline_length(map: &Grid<u32>, mut ray: impl Iterator<Item = Point>) -> u32 {
+    let house_height = map[ray.next().unwrap()];
     let mut length = 0;
-    for (i, j) in iter {
-        let h = get_height(map, i, j);
+    for pos in ray {
         length += 1;
-        if h >= house_height {
+        if map[pos] >= house_height {
             return length;
         }
     }
@@ -145,45 +61,38 @@ fn sightline_length(map: &Vec<Vec<u32>>, iter: &mut Iter2d) -> u32 {
 }
 
 #[aoc(day8, part1)]
-fn part1(input: &str) -> usize {
-    let map = parse(input);
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let map = parse(input)?;
     let vis_map = check_visible(&map);
 
-    //print_debug(&map, &vis_map);
+    if crate::trace::is_enabled(8) {
+        print_debug(&map, &vis_map);
+    }
 
-    vis_map
-        .iter()
-        .map(|line| line.iter().filter(|b| **b).count())
-        .sum()
+    Ok(vis_map.iter().filter(|(_, b)| **b).count())
 }
 
 #[aoc(day8, part2)]
-fn part2(input: &str) -> u32 {
-    let map = parse(input);
-
-    let mut best = 0;
-
-    for i in 0..map.first().unwrap().len() {
-        for j in 0..map.len() {
-            let score = score_view(&map, i, j);
-            if score > best {
-                best = score;
-            }
-        }
-    }
-    best
+fn part2(input: &str) -> Result<u32, ParseError> {
+    let map = parse(input)?;
+
+    Ok(map
+        .points()
+        .map(|pos| score_view(&map, pos))
+        .max()
+        .unwrap_or(0))
 }
 
-fn print_debug(map: &Vec<Vec<u32>>, vis: &Vec<Vec<bool>>) {
-    for i in 0..map.first().unwrap().len() {
-        for j in 0..map.len() {
-            if *vis.get(i).unwrap().get(j).unwrap() {
-                print!("{}", get_height(&map, i, j));
-            } else {
-                print!("\u{001B}[41m{}\u{001B}[0m", get_height(&map, i, j));
-            }
+fn print_debug(map: &Grid<u32>, vis: &Grid<bool>) {
+    for (pos, h) in map.iter() {
+        if vis[pos] {
+            eprint!("{}", h);
+        } else {
+            eprint!("\u{001B}[41m{}\u{001B}[0m", h);
+        }
+        if pos.0 as usize == map.width() - 1 {
+            eprintln!();
         }
-        print!("\n");
     }
 }
 
@@ -197,22 +106,28 @@ mod test {
 33549
 35390";
 
+    fn rows<T: Clone>(grid: &Grid<T>) -> Vec<Vec<T>> {
+        (0..grid.height())
+            .map(|y| grid.row(y).cloned().collect())
+            .collect()
+    }
+
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 21)
+        assert_eq!(part1(EXAMPLE), Ok(21))
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 8)
+        assert_eq!(part2(EXAMPLE), Ok(8))
     }
 
     #[test]
     fn test_parser() {
-        let out = parse(EXAMPLE);
+        let out = parse(EXAMPLE).unwrap();
 
         assert_eq!(
-            out,
+            rows(&out),
             vec![
                 vec![3, 0, 3, 7, 3],
                 vec![2, 5, 5, 1, 2],
@@ -222,12 +137,20 @@ mod test {
             ]
         )
     }
+    #[test]
+    fn test_parser_error() {
+        let e = parse("303\n2x5").unwrap_err();
+
+        assert_eq!((e.line, e.column), (2, 2));
+        assert_eq!(e.text, "x");
+    }
+
     #[test]
     fn test_vis() {
-        let out = check_visible(&parse(EXAMPLE));
+        let out = check_visible(&parse(EXAMPLE).unwrap());
 
         assert_eq!(
-            out,
+            rows(&out),
             vec![
                 vec![true, true, true, true, true],
                 vec![true, true, true, false, true],
@@ -240,9 +163,9 @@ mod test {
 
     #[test]
     fn test_score() {
-        let map = parse(EXAMPLE);
+        let map = parse(EXAMPLE).unwrap();
 
-        assert_eq!(score_view(&map, 1, 2), 4);
-        assert_eq!(score_view(&map, 3, 2), 8);
+        assert_eq!(score_view(&map, Point(2, 1)), 4);
+        assert_eq!(score_view(&map, Point(2, 3)), 8);
     }
 }
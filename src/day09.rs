@@ -1,10 +1,9 @@
-use std::{
-    cmp::{max, min},
-    collections::HashSet,
-    ops::AddAssign,
-};
+use std::collections::HashSet;
 
-use Direction::*;
+use crate::{
+    geometry::{BoundingBox, Direction, Direction::*, Point},
+    parse::{parse_num, ParseError},
+};
 
 #[derive(Debug, PartialEq)]
 struct Move {
@@ -18,60 +17,25 @@ impl Move {
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum Direction {
-    U,
-    D,
-    L,
-    R,
-}
-
-impl Direction {
-    fn get_tuple(&self) -> Point {
-        match self {
-            U => Point(-1, 0),
-            D => Point(1, 0),
-            L => Point(0, -1),
-            R => Point(0, 1),
-        }
-    }
-}
-
-#[derive(Debug, Eq, Hash, PartialEq, Clone)]
-pub struct Point(pub i32, pub i32);
-
-impl AddAssign for Point {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
-    }
-}
-
-impl std::ops::Add for Point {
-    type Output = Point;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        let x = self.0 + rhs.0;
-        let y = self.1 + rhs.1;
-        Point(x, y)
-    }
-}
-
 #[aoc_generator(day9)]
-fn read(input: &str) -> Vec<Move> {
+fn read(input: &str) -> Result<Vec<Move>, ParseError> {
     input
         .lines()
         .map(|l| {
             let mut words = l.split_whitespace();
-            let dir = match words.next().unwrap() {
-                "U" => U,
-                "D" => D,
-                "L" => L,
-                "R" => R,
-                _ => panic!("Unkown Direction"),
+            let dir = match words.next() {
+                Some("U") => U,
+                Some("D") => D,
+                Some("L") => L,
+                Some("R") => R,
+                Some(w) => return Err(ParseError::new(9, input, w, "Unkown Direction")),
+                None => return Err(ParseError::missing(9, input, l, "direction")),
+            };
+            let size = match words.next() {
+                Some(w) => parse_num(9, input, w)?,
+                None => return Err(ParseError::missing(9, input, l, "length")),
             };
-            let size = words.next().unwrap().parse().unwrap();
-            Move::new(dir, size)
+            Ok(Move::new(dir, size))
         })
         .collect()
 }
@@ -90,7 +54,7 @@ impl State {
     }
 
     fn do_move(&mut self, mov: &Move) {
-        //println!("Do_move {:#?}", mov);
+        trace!(9, "do_move {:?}", mov);
         for _ in 0..mov.length {
             self.move_step(&mov.direction);
         }
@@ -98,109 +62,43 @@ impl State {
 
     // moves one step
     fn move_step(&mut self, dir: &Direction) {
-        *self.links.get_mut(0).unwrap() += dir.get_tuple();
+        self.links[0] += dir.offset();
 
         for i in 1..self.links.len() {
-            let pos = State::get_pos(self.links.get(i - 1).unwrap(), self.links.get(i).unwrap());
-            *self.links.get_mut(i).unwrap() = pos;
+            self.links[i] = State::get_pos(&self.links[i - 1], &self.links[i]);
         }
 
-        self.visited.insert(self.links.last().unwrap().clone());
+        self.visited.insert(*self.links.last().unwrap());
 
-        //println!("done step {:?} \n{}", dir, self);
+        trace!(9, "done step {:?}\n{}", dir, self);
     }
 
+    // the tail only follows once it does not touch the head anymore
     fn get_pos(h_pos: &Point, t_pos: &Point) -> Point {
-        let mut new_pos = t_pos.clone();
-        // non diagonal
-        if h_pos.0 == t_pos.0 || t_pos.1 == h_pos.1 {
-            // ud
-            match h_pos.0 - t_pos.0 {
-                2 => new_pos.0 += 1,
-                -1..=1 => (),
-                -2 => new_pos.0 -= 1,
-                _ => panic!("tail to far u/d"),
-            }
-
-            // lr
-            match h_pos.1 - t_pos.1 {
-                2 => new_pos.1 += 1,
-                -1..=1 => (),
-                -2 => new_pos.1 -= 1,
-                _ => panic!("tail to far l/r"),
-            }
-        } else if (t_pos.0 - h_pos.0).abs() + (t_pos.1 - h_pos.1).abs() > 2 {
-            // ud
-            match h_pos.0 - t_pos.0 {
-                1..=2 => new_pos.0 += 1,
-                0 => (),
-                -2..=-1 => new_pos.0 -= 1,
-                _ => panic!("tail to far u/d"),
-            }
-
-            // lr
-            match h_pos.1 - t_pos.1 {
-                1..=2 => new_pos.1 += 1,
-                0 => (),
-                -2..=-1 => new_pos.1 -= 1,
-                _ => panic!("tail to far l/r"),
-            }
+        if h_pos.chebyshev_dist(t_pos) > 1 {
+            *t_pos + (*h_pos - *t_pos).signum()
+        } else {
+            *t_pos
         }
-
-        new_pos
     }
 }
 
 impl std::fmt::Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ub = min(
-            *self.visited.iter().map(|Point(a, _)| a).min().unwrap_or(&0),
-            *self.links.iter().map(|Point(a, _)| a).min().unwrap_or(&0),
-        );
-        let db = max(
-            *self
-                .visited
-                .iter()
-                .map(|Point(a, _)| a)
-                .max()
-                .unwrap_or(&std::i32::MAX),
-            *self
-                .links
-                .iter()
-                .map(|Point(a, _)| a)
-                .max()
-                .unwrap_or(&std::i32::MAX),
-        );
-        let lb = min(
-            *self.visited.iter().map(|Point(_, a)| a).min().unwrap_or(&0),
-            *self.links.iter().map(|Point(_, a)| a).min().unwrap_or(&0),
-        );
-        let rb = max(
-            *self
-                .visited
-                .iter()
-                .map(|Point(_, a)| a)
-                .max()
-                .unwrap_or(&std::i32::MAX),
-            *self
-                .links
-                .iter()
-                .map(|Point(_, a)| a)
-                .max()
-                .unwrap_or(&std::i32::MAX),
-        );
+        let bb = BoundingBox::from_points(self.visited.iter().chain(&self.links)).unwrap();
 
         let mut chars: Vec<char> = Vec::new();
 
-        for row in ub..=db {
-            for p in lb..=rb {
-                if *self.links.first().unwrap() == Point(row, p) {
+        for y in bb.min.1..=bb.max.1 {
+            for x in bb.min.0..=bb.max.0 {
+                let p = Point(x, y);
+                if *self.links.first().unwrap() == p {
                     chars.push('H');
-                } else if *self.links.last().unwrap() == Point(row, p) {
+                } else if *self.links.last().unwrap() == p {
                     chars.push('T');
-                } else if self.links.contains(&Point(row, p)) {
+                } else if self.links.contains(&p) {
                     chars.push('*');
-                } else if self.visited.contains(&Point(row, p)) {
+                } else if self.visited.contains(&p) {
                     chars.push('#');
                 } else {
                     chars.push('.');
@@ -264,7 +162,33 @@ U 20";
 
     #[test]
     fn test_reader() {
-        assert_eq!(read(EXAMPLE_STR), load())
+        assert_eq!(read(EXAMPLE_STR), Ok(load()))
+    }
+
+    #[test]
+    fn test_reader_error() {
+        let e = read("R 4\nX 4").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "X"));
+
+        let e = read("R 4\nU").unwrap_err();
+        assert_eq!((e.line, e.column, e.msg.as_str()), (2, 2, "missing length"));
+    }
+
+    #[test]
+    fn test_follow() {
+        assert_eq!(State::get_pos(&Point(1, 1), &Point(0, 0)), Point(0, 0));
+        assert_eq!(State::get_pos(&Point(2, 0), &Point(0, 0)), Point(1, 0));
+        assert_eq!(State::get_pos(&Point(2, 1), &Point(0, 0)), Point(1, 1));
+        assert_eq!(State::get_pos(&Point(-2, -2), &Point(0, 0)), Point(-1, -1));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut s = State::new(1);
+        s.do_move(&Move::new(R, 2));
+        s.do_move(&Move::new(U, 1));
+
+        assert_eq!(s.to_string(), "..H\n#T.");
     }
 
     #[test]
@@ -279,6 +203,6 @@ U 20";
 
     #[test]
     fn part2_test_big() {
-        assert_eq!(part2(&read(EXAMPLE_STR_BIG)[..]), 36)
+        assert_eq!(part2(&read(EXAMPLE_STR_BIG).unwrap()[..]), 36)
     }
 }
@@ -1,16 +1,21 @@
 use Cmd::*;
 
+use crate::parse::{parse_num, ParseError};
+
 #[aoc_generator(day10)]
-fn read(input: &str) -> Vec<Cmd> {
+fn read(input: &str) -> Result<Vec<Cmd>, ParseError> {
     input
         .lines()
         .map(|l| {
             if l == "noop" {
-                return Noop;
+                return Ok(Noop);
             } else if l.starts_with("addx") {
-                return Addx(l.split_whitespace().nth(1).unwrap().parse().unwrap());
+                return match l.split_whitespace().nth(1) {
+                    Some(v) => Ok(Addx(parse_num(10, input, v)?)),
+                    None => Err(ParseError::missing(10, input, l, "value")),
+                };
             } else {
-                panic!("cannot read line");
+                Err(ParseError::new(10, input, l, "cannot read line"))
             }
         })
         .collect()
@@ -39,8 +44,7 @@ impl Cpu {
     }
 
     fn do_cmd(&mut self, cmd: &Cmd) {
-        //println!("{:#?}", self);
-        //        println!("Cmd: {:?}", cmd);
+        trace!(10, "{:?} cmd: {:?}", self, cmd);
         match cmd {
             Noop => self.tick(1),
             Addx(x) => {
@@ -94,7 +98,7 @@ fn part2(input: &[Cmd]) -> String {
     for _line in 1..=6 {
         for col in 1..=40 {
             let x_at_cycle = cpu.history.iter().nth(cycle.try_into().unwrap()).unwrap();
-            //println!("idx: {} x: {} scan: {}", cycle, x_at_cycle, col);
+            trace!(10, "idx: {} x: {} scan: {}", cycle, x_at_cycle, col);
             if (x_at_cycle + 1 - col).abs() < 2
                 || (x_at_cycle, col) == (&0, 40)
                 || (x_at_cycle, col) == (&40, 0)
@@ -104,7 +108,6 @@ fn part2(input: &[Cmd]) -> String {
                 re.push('.')
             }
             cycle += 1;
-            //print!("{}", re);
         }
         re.push('\n');
     }
@@ -119,12 +122,20 @@ mod test {
     fn test_reader() {
         let l = read("addx 20\nnoop\naddx -11");
 
-        assert_eq!(l, vec![Addx(20), Noop, Addx(-11)]);
+        assert_eq!(l, Ok(vec![Addx(20), Noop, Addx(-11)]));
+    }
+
+    #[test]
+    fn test_reader_error() {
+        let e = read("noop\naddx 2\naddx two").unwrap_err();
+
+        assert_eq!((e.line, e.column), (3, 6));
+        assert_eq!(e.text, "two");
     }
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(&read(EXAMPLE)[..]), 13140)
+        assert_eq!(part1(&read(EXAMPLE).unwrap()[..]), 13140)
     }
 
     #[test]
@@ -135,7 +146,7 @@ mod test {
 #####.....#####.....#####.....#####.....
 ######......######......######......####
 #######.......#######.......#######.....\n";
-        assert_eq!(part2(&read(EXAMPLE)[..]), exp)
+        assert_eq!(part2(&read(EXAMPLE).unwrap()[..]), exp)
     }
 
     const EXAMPLE: &str = "addx 15
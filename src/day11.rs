@@ -1,60 +1,77 @@
+use std::str::FromStr;
+
 use Operator::*;
 
-fn read(input: &str) -> Vec<Monkey> {
+use crate::parse::{parse_num, ParseError};
+
+fn read(input: &str) -> Result<Vec<Monkey>, ParseError> {
     let mut mk_list = Vec::new();
+    // target numbers, checked once all monkeys are known
+    let mut targets = Vec::new();
     let mk_block_list = input.split("\n\n");
 
     for block in mk_block_list {
         let mut lines = block.lines().skip(1);
-        let num_list_str = lines.next().unwrap().split(":").skip(1).next().unwrap();
+        let mut next_line = |what| {
+            lines
+                .next()
+                .ok_or_else(|| ParseError::missing(11, input, block, what))
+        };
+
+        let items_line = next_line("starting items")?;
+        let num_list_str = items_line
+            .split(':')
+            .nth(1)
+            .ok_or_else(|| ParseError::missing(11, input, items_line, "items"))?;
         let items: Vec<i64> = num_list_str
-            .split(",")
-            .map(|s| s.trim().parse::<i64>().unwrap())
-            .collect();
+            .split(',')
+            .map(|s| parse_num(11, input, s.trim()))
+            .collect::<Result<_, _>>()?;
+        let op_line = next_line("operation")?;
+        let op = Op::new(op_line).map_err(|e| e.within(input, op_line))?;
+        let test_line = next_line("test")?;
+        let test_div = last_num(input, test_line)?;
+        if test_div < 1 {
+            let word = last_word(input, test_line)?;
+            return Err(ParseError::new(
+                11,
+                input,
+                word,
+                "expected a divisor above 0",
+            ));
+        }
+        let true_word = last_word(input, next_line("true target")?)?;
+        let false_word = last_word(input, next_line("false target")?)?;
+        targets.extend([true_word, false_word]);
+
         // read items
         mk_list.push(Monkey {
             items: items.clone(),
-            op: {
-                let mut op_str = lines
-                    .next()
-                    .unwrap()
-                    .splitn(2, "old")
-                    .skip(1)
-                    .next()
-                    .unwrap()
-                    .split_whitespace();
-
-                Op::new(op_str.next().unwrap(), op_str.next().unwrap())
-            },
-            test_div: lines
-                .next()
-                .unwrap()
-                .split_whitespace()
-                .last()
-                .unwrap()
-                .parse()
-                .unwrap(),
-            true_idx: lines
-                .next()
-                .unwrap()
-                .split_whitespace()
-                .last()
-                .unwrap()
-                .parse()
-                .unwrap(),
-            false_idx: lines
-                .next()
-                .unwrap()
-                .split_whitespace()
-                .last()
-                .unwrap()
-                .parse()
-                .unwrap(),
+            op,
+            test_div,
+            true_idx: parse_num(11, input, true_word)?,
+            false_idx: parse_num(11, input, false_word)?,
             inspections_done: 0,
         })
     }
 
-    mk_list
+    for word in targets {
+        if parse_num::<usize>(11, input, word)? >= mk_list.len() {
+            return Err(ParseError::new(11, input, word, "no such monkey"));
+        }
+    }
+
+    Ok(mk_list)
+}
+
+fn last_word<'a>(input: &str, line: &'a str) -> Result<&'a str, ParseError> {
+    line.split_whitespace()
+        .last()
+        .ok_or_else(|| ParseError::missing(11, input, line, "number"))
+}
+
+fn last_num<T: FromStr>(input: &str, line: &str) -> Result<T, ParseError> {
+    parse_num(11, input, last_word(input, line)?)
 }
 
 #[derive(Debug, PartialEq)]
@@ -135,23 +152,35 @@ struct FlyingItem {
 }
 
 impl Op {
-    fn new(op_str: &str, number: &str) -> Self {
+    // reads the operation line, like "Operation: new = old * 19"
+    fn new(line: &str) -> Result<Self, ParseError> {
+        let mut op_str = line
+            .splitn(2, "old")
+            .nth(1)
+            .ok_or_else(|| ParseError::missing(11, line, line, "old"))?
+            .split_whitespace();
+
+        let (op_str, number) = match (op_str.next(), op_str.next()) {
+            (Some(op_str), Some(number)) => (op_str, number),
+            _ => return Err(ParseError::missing(11, line, line, "operand")),
+        };
+
         if number == "old" {
-            Self {
+            Ok(Self {
                 op: Square,
                 number: None,
-            }
+            })
         } else {
             match op_str {
-                "*" => Self {
+                "*" => Ok(Self {
                     op: Mult,
-                    number: Some(number.parse().unwrap()),
-                },
-                "+" => Self {
+                    number: Some(parse_num(11, line, number)?),
+                }),
+                "+" => Ok(Self {
                     op: Plus,
-                    number: Some(number.parse().unwrap()),
-                },
-                _ => panic!("invald op"),
+                    number: Some(parse_num(11, line, number)?),
+                }),
+                _ => Err(ParseError::new(11, line, op_str, "invald op")),
             }
         }
     }
@@ -165,14 +194,14 @@ enum Operator {
 }
 
 #[aoc(day11, part1)]
-fn part1(input: &str) -> usize {
-    let mut mk_list = read(input);
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let mut mk_list = read(input)?;
 
     for _ in 0..20 {
         do_round(&mut mk_list);
     }
 
-    monkey_fun(&mk_list)
+    Ok(monkey_fun(&mk_list))
 }
 
 fn do_round(mk_list: &mut [Monkey]) {
@@ -192,8 +221,8 @@ fn monkey_fun(mk_list: &[Monkey]) -> usize {
 }
 
 #[aoc(day11, part2)]
-fn part2(input: &str) -> usize {
-    let mut mk_list = read(input);
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let mut mk_list = read(input)?;
 
     let prime_prod: i64 = mk_list.iter().map(|m| m.test_div).product();
 
@@ -201,7 +230,7 @@ fn part2(input: &str) -> usize {
         do_round_smart(&mut mk_list, prime_prod);
     }
 
-    monkey_fun(&mk_list)
+    Ok(monkey_fun(&mk_list))
 }
 
 fn do_round_smart(mk_list: &mut [Monkey], prime_prod: i64) {
@@ -247,7 +276,7 @@ Monkey 3:
 
     #[test]
     fn test_read() {
-        let ml = read(EXAMPLE);
+        let ml = read(EXAMPLE).unwrap();
         let mut ml = ml.iter();
 
         assert_eq!(
@@ -296,14 +325,35 @@ Monkey 3:
         );
     }
 
+    #[test]
+    fn test_read_error() {
+        let input = EXAMPLE.replace("old + 6", "old - 6");
+        let e = read(&input).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (10, 24, "-"));
+
+        let input = EXAMPLE.replace("79, 60, 97", "79, 6o, 97");
+        let e = read(&input).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (16, 23, "6o"));
+
+        let input = EXAMPLE.replace("divisible by 19", "divisible by 0");
+        let e = read(&input).unwrap_err();
+        assert_eq!((e.line, e.column), (11, 22));
+        assert_eq!(e.msg, "expected a divisor above 0");
+
+        let input = EXAMPLE.replace("If false: throw to monkey 3", "If false: throw to monkey 4");
+        let e = read(&input).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (6, 31, "4"));
+        assert_eq!(e.msg, "no such monkey");
+    }
+
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 10605)
+        assert_eq!(part1(EXAMPLE), Ok(10605))
     }
 
     #[test]
     #[ignore = "slow (>15 ms)"]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 2713310158)
+        assert_eq!(part2(EXAMPLE), Ok(2713310158))
     }
 }
@@ -1,30 +1,110 @@
-use std::collections::{HashMap, HashSet};
+use std::{collections::HashMap, fmt::Display};
 
 use Tile::*;
 
-use crate::day09::Point;
+use crate::{
+    geometry::Point,
+    grid::Grid,
+    parse::ParseError,
+    path::{bfs, Search},
+};
 
 #[aoc_generator(day12)]
-fn read(input: &str) -> Vec<Vec<Tile>> {
-    input
-        .lines()
-        .map(|l| l.chars().map(|c| Tile::new(c)).collect())
-        .collect()
+fn read(input: &str) -> Result<Grid<Tile>, ParseError> {
+    read_with(input, &Rules::default())
+}
+
+// exactly one start and one end are needed
+fn read_with(input: &str, rules: &Rules) -> Result<Grid<Tile>, ParseError> {
+    let tiles = Grid::parse(12, input, |c| Tile::new(c, rules))?;
+
+    for (tile, symbol, name) in [(Start, rules.start, "start"), (End, rules.end, "end")] {
+        let found: Vec<Point> = tiles
+            .iter()
+            .filter(|(_, t)| **t == tile)
+            .map(|(p, _)| p)
+            .collect();
+        match found[..] {
+            [_] => (),
+            [] => {
+                let last = input.lines().last().unwrap_or(input);
+                let what = format!("{} {}", name, symbol);
+                return Err(ParseError::missing(12, input, last, &what));
+            }
+            [_, second, ..] => {
+                let msg = format!("more than one {}", name);
+                return Err(ParseError::new(12, input, cell_text(input, second), &msg));
+            }
+        }
+    }
+    Ok(tiles)
+}
+
+// the char of the input at a grid position
+fn cell_text(input: &str, p: Point) -> &str {
+    let line = input.lines().nth(p.1 as usize).unwrap();
+    let (i, c) = line.char_indices().nth(p.0 as usize).unwrap();
+    &line[i..i + c.len_utf8()]
+}
+
+// what counts as a legal move, the defaults are the puzzle rules
+#[derive(Debug, Clone)]
+pub struct Rules {
+    pub max_ascent: usize,
+    // None allows any drop
+    pub max_descent: Option<usize>,
+    pub diagonal: bool,
+    pub start: char,
+    pub end: char,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            max_ascent: 1,
+            max_descent: None,
+            diagonal: false,
+            start: 'S',
+            end: 'E',
+        }
+    }
+}
+
+impl Rules {
+    fn can_step(&self, from: usize, to: usize) -> bool {
+        to <= from + self.max_ascent && self.max_descent.is_none_or(|d| from <= to + d)
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-enum Tile {
+pub enum Tile {
     Height(usize),
     Start,
     End,
 }
 
 impl Tile {
-    fn new(c: char) -> Tile {
+    fn new(c: char, rules: &Rules) -> Result<Tile, String> {
         match c {
-            'S' => Start,
-            'E' => End,
-            _ => Height((c as u32 - 48 - 49).try_into().unwrap()),
+            _ if c == rules.start && c == rules.end => Err(format!("{} is both start and end", c)),
+            'a'..='z' if c == rules.start || c == rules.end => {
+                Err(format!("{} is both a height and the start or end", c))
+            }
+            _ if c == rules.start => Ok(Start),
+            _ if c == rules.end => Ok(End),
+            'a'..='z' => Ok(Height((c as u8 - b'a') as usize)),
+            _ => Err(format!(
+                "expected a height a-z, {} or {}",
+                rules.start, rules.end
+            )),
+        }
+    }
+
+    fn symbol(&self, rules: &Rules) -> char {
+        match self {
+            Height(h) => (b'a' + *h as u8) as char,
+            Start => rules.start,
+            End => rules.end,
         }
     }
 
@@ -37,127 +117,184 @@ impl Tile {
     }
 }
 
-struct Map {
-    tiles: Vec<Vec<Tile>>,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Plain,
+    Ansi,
+}
+
+// from close to far
+const SHADES: &[u8] = b"@%#*+=-:. ";
+
+pub struct Map {
+    tiles: Grid<Tile>,
+    rules: Rules,
 }
 
 impl Map {
-    fn new(tiles: Vec<Vec<Tile>>) -> Self {
-        Self { tiles }
-    }
-    fn find_path(&self, start: Point) -> Option<Vec<Point>> {
-        let mut min_steps = HashMap::new();
-        let mut q = HashSet::new();
-        q.insert(vec![start]);
-
-        while !q.is_empty() {
-            let path_ref = q
-                .iter()
-                .min_by_key(|p| p.len() + self.get_tile(p.last().unwrap()).unwrap().height())
-                .cloned()
-                .unwrap();
-            let path = q.take(&path_ref).unwrap();
-            let node = path.last().unwrap().clone();
-            if self.get_tile(&node) == Some(End) {
-                //println!("found path {:#?}", path);
-                return Some(path);
-            } else {
-                for next_pos in self.adj(&node) {
-                    let new_cost = path.len() + 1 + self.get_tile(&next_pos).unwrap().height();
-                    if let Some(min_cost) = min_steps.get(&next_pos) {
-                        if *min_cost > new_cost {
-                            let mut new_path = path.clone();
-                            new_path.push(next_pos.clone());
-                            q.insert(new_path);
-                            min_steps.insert(next_pos.clone(), new_cost);
-                        }
-                    } else {
-                        let mut new_path = path.clone();
-                        new_path.push(next_pos.clone());
-                        q.insert(new_path);
-                        min_steps.insert(next_pos.clone(), new_cost);
-                    }
-                }
-            }
+    pub fn new(tiles: Grid<Tile>) -> Self {
+        Self {
+            tiles,
+            rules: Rules::default(),
         }
+    }
 
-        None
+    pub fn parse(input: &str, rules: Rules) -> Result<Self, ParseError> {
+        let tiles = read_with(input, &rules)?;
+        Ok(Self { tiles, rules })
     }
 
-    fn find_start(&self) -> Point {
-        for (x, row) in self.tiles.iter().enumerate() {
-            for (y, t) in row.iter().enumerate() {
-                if let Start = t {
-                    return Point(x.try_into().unwrap(), y.try_into().unwrap());
-                }
-            }
+    // shortest route from start up to the summit
+    pub fn find_path(&self, start: Point) -> Option<Vec<Point>> {
+        let mut path = self.descend().path_to(&start)?;
+        path.reverse();
+        trace!(12, "found path {:?}", path);
+        Some(path)
+    }
+
+    // walks down from the end, so one search covers every start
+    fn descend(&self) -> Search<Point> {
+        let end = self.tiles.find(|t| *t == End).expect("No end found");
+        bfs([end], |p| self.climbable_from(p), |_| false)
+    }
+
+    // steps to the summit for every cell, None if it cannot be reached
+    pub fn summit_distances(&self) -> Grid<Option<usize>> {
+        let mut dist = Grid::new(self.tiles.width(), self.tiles.height(), None);
+        for (p, d) in self.descend().distances() {
+            dist[*p] = Some(*d);
         }
-        panic!("No start found")
+        dist
+    }
+
+    pub fn cut_off(&self) -> Vec<Point> {
+        self.summit_distances()
+            .iter()
+            .filter(|(_, d)| d.is_none())
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    fn find_start(&self) -> Point {
+        self.tiles.find(|t| *t == Start).expect("No start found")
     }
 
     fn get_points_of_height(&self, height: usize) -> Vec<Point> {
-        let mut re = Vec::new();
-        for (x, row) in self.tiles.iter().enumerate() {
-            for (y, t) in row.iter().enumerate() {
-                if t.height() == height {
-                    re.push(Point(x.try_into().unwrap(), y.try_into().unwrap()));
-                }
-            }
-        }
-        re
+        self.tiles
+            .iter()
+            .filter(|(_, t)| t.height() == height)
+            .map(|(p, _)| p)
+            .collect()
     }
 
-    fn adj(&self, pos: &Point) -> Vec<Point> {
-        let curr_height = self.get_tile(pos).expect("start point not found").height();
+    // neighbours with a legal step to pos
+    fn climbable_from(&self, pos: &Point) -> Vec<Point> {
+        let curr_height = self.tiles[*pos].height();
+        let neighbours: Vec<Point> = if self.rules.diagonal {
+            self.tiles.neighbours8(*pos).collect()
+        } else {
+            self.tiles.neighbours4(*pos).collect()
+        };
 
-        vec![Point(1, 0), Point(-1, 0), Point(0, 1), Point(0, -1)]
+        neighbours
             .into_iter()
-            .map(|m| pos.clone() + m)
-            .filter(|p| {
-                if let Some(t) = self.get_tile(p) {
-                    t.height() <= curr_height + 1
-                } else {
-                    false
-                }
-            })
+            .filter(|p| self.rules.can_step(self.tiles[*p].height(), curr_height))
             .collect()
     }
 
-    fn get_tile(&self, pos: &Point) -> Option<Tile> {
-        let Point(x, y) = pos.clone();
-
-        if x < 0 || y < 0 {
-            return None;
+    // height letters with the path drawn as arrows, shading marks the distance to the summit
+    pub fn render(&self, path: &[Point], style: Style, shade: bool) -> String {
+        let mut arrows = HashMap::new();
+        for step in path.windows(2) {
+            arrows.insert(step[0], arrow(step[1] - step[0]));
         }
+        let dist = self.summit_distances();
+        let max = dist
+            .iter()
+            .filter_map(|(_, d)| *d)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        let mut re = String::new();
+        for (pos, tile) in self.tiles.iter() {
+            let on_path = arrows.contains_key(&pos);
+            let c = match tile {
+                Start | End => tile.symbol(&self.rules),
+                _ if on_path => arrows[&pos],
+                _ if shade && style == Style::Plain => match dist[pos] {
+                    Some(d) => SHADES[d * (SHADES.len() - 1) / max] as char,
+                    None => ' ',
+                },
+                _ => tile.symbol(&self.rules),
+            };
 
-        if let Some(row) = self.tiles.iter().nth(x.try_into().unwrap()) {
-            if let Some(tile) = row.iter().nth(y.try_into().unwrap()) {
-                return Some(tile.clone());
+            match style {
+                Style::Plain => re.push(c),
+                Style::Ansi if on_path || *tile == End => {
+                    re += &format!("\u{001B}[41m{}\u{001B}[0m", c)
+                }
+                Style::Ansi => match dist[pos] {
+                    Some(d) if shade => {
+                        let grey = 255 - d * 23 / max;
+                        re += &format!("\u{001B}[48;5;{}m\u{001B}[30m{}\u{001B}[0m", grey, c)
+                    }
+                    _ => re.push(c),
+                },
+            }
+
+            if pos.0 as usize == self.tiles.width() - 1 {
+                re.push('\n');
             }
         }
-        None
+        re
+    }
+}
+
+// steps that are not between neighbours are marked with '?'
+// the map as it was read, with the symbols of its rules
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tiles.map(|t| t.symbol(&self.rules)))
+    }
+}
+
+fn arrow(step: Point) -> char {
+    match step {
+        Point(1, 0) => '>',
+        Point(-1, 0) => '<',
+        Point(0, -1) => '^',
+        Point(0, 1) => 'v',
+        Point(1, -1) => '↗',
+        Point(1, 1) => '↘',
+        Point(-1, 1) => '↙',
+        Point(-1, -1) => '↖',
+        _ => '?',
     }
 }
 
 #[aoc(day12, part1)]
-fn part1(input: &[Vec<Tile>]) -> usize {
-    let map = Map::new(input.to_vec());
-    map.find_path(map.find_start())
-        .expect("no path found")
-        .len()
-        - 1
+fn part1(input: &Grid<Tile>) -> usize {
+    let map = Map::new(input.clone());
+
+    if crate::trace::is_enabled(12) {
+        if let Some(path) = map.find_path(map.find_start()) {
+            eprint!("{}", map.render(&path, Style::Ansi, true));
+        }
+    }
+
+    map.summit_distances()[map.find_start()].expect("no path found")
 }
 
 #[aoc(day12, part2)]
-fn part2(input: &[Vec<Tile>]) -> usize {
-    let map = Map::new(input.to_vec());
+fn part2(input: &Grid<Tile>) -> usize {
+    let map = Map::new(input.clone());
+    let dist = map.summit_distances();
     map.get_points_of_height(0)
         .iter()
-        .map(|start| map.find_path(start.clone()))
-        .filter_map(|path| path.map(|p| p.len()))
+        .filter_map(|start| dist[*start])
         .min()
         .unwrap()
-        - 1
 }
 
 #[cfg(test)]
@@ -172,24 +309,161 @@ abdefghi";
 
     #[test]
     fn test_read_input() {
-        let map = read("Sab\nyzE");
+        let map = read("Sab\nyzE").unwrap();
 
         assert_eq!(
-            map,
-            vec![
-                vec![Start, Height(0), Height(1)],
-                vec![Height(24), Height(25), End],
-            ]
-        )
+            map.row(0).cloned().collect::<Vec<_>>(),
+            vec![Start, Height(0), Height(1)]
+        );
+        assert_eq!(
+            map.row(1).cloned().collect::<Vec<_>>(),
+            vec![Height(24), Height(25), End]
+        );
+        assert_eq!(Map::new(map).to_string(), "Sab\nyzE");
+    }
+
+    #[test]
+    fn test_read_error() {
+        let e = read("Sab\ny#E").unwrap_err();
+
+        assert_eq!((e.line, e.column), (2, 2));
+        assert_eq!(e.text, "#");
     }
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(&read(EXAMPLE)[..]), 31)
+        assert_eq!(part1(&read(EXAMPLE).unwrap()), 31)
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(&read(EXAMPLE)[..]), 29)
+        assert_eq!(part2(&read(EXAMPLE).unwrap()), 29)
+    }
+
+    #[test]
+    fn test_find_path() {
+        let map = Map::new(read(EXAMPLE).unwrap());
+        let path = map.find_path(map.find_start()).unwrap();
+
+        assert_eq!(path.len(), 32);
+        assert_eq!(path.first(), Some(&Point(0, 0)));
+        assert_eq!(path.last(), Some(&Point(5, 2)));
+    }
+
+    #[test]
+    fn test_summit_distances() {
+        let map = Map::new(read("SbzE").unwrap());
+        let dist = map.summit_distances();
+
+        assert_eq!(
+            dist.row(0).copied().collect::<Vec<_>>(),
+            vec![None, None, Some(1), Some(0)]
+        );
+        assert_eq!(map.cut_off(), vec![Point(0, 0), Point(1, 0)]);
+    }
+
+    #[test]
+    fn test_render() {
+        let map = Map::new(read(EXAMPLE).unwrap());
+        let path = map.find_path(map.find_start()).unwrap();
+
+        assert_eq!(
+            map.render(&path, Style::Plain, false),
+            "S>vv<<<<\nabvvv<<^\nacvv>E^^\nacv>>>^^\nab>>>>>^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_shaded() {
+        let map = Map::new(read("SbcdE\nzzzzz").unwrap());
+
+        assert_eq!(map.render(&[], Style::Plain, true), "S   E\n :=*%\n");
+        let ansi = map.render(&[], Style::Ansi, true);
+        assert!(ansi.starts_with("Sbcd\u{001B}[41mE"));
+        assert!(ansi.contains("\n\u{001B}[48;5;232m\u{001B}[30mz"));
+    }
+
+    #[test]
+    fn test_rules() {
+        let rules = Rules {
+            start: '@',
+            end: '$',
+            ..Rules::default()
+        };
+        let input = EXAMPLE.replace('S', "@").replace('E', "$");
+        let map = Map::parse(&input, rules).unwrap();
+        assert_eq!(map.summit_distances()[map.find_start()], Some(31));
+        assert_eq!(map.to_string(), input);
+
+        let diagonal = Rules {
+            diagonal: true,
+            ..Rules::default()
+        };
+        let map = Map::parse(EXAMPLE, diagonal).unwrap();
+        let path = map.find_path(map.find_start()).unwrap();
+        assert!(path.len() < 32);
+        assert!(map.render(&path, Style::Plain, false).contains('↘'));
+    }
+
+    #[test]
+    fn test_rules_descent() {
+        // the only way leads over z and drops back to a
+        let jump = Rules {
+            max_ascent: 25,
+            ..Rules::default()
+        };
+        let map = Map::parse("SzaE", jump.clone()).unwrap();
+        assert_eq!(map.summit_distances()[Point(0, 0)], Some(3));
+
+        let careful = Rules {
+            max_descent: Some(1),
+            ..jump
+        };
+        let map = Map::parse("SzaE", careful).unwrap();
+        assert_eq!(map.summit_distances()[Point(0, 0)], None);
+    }
+
+    #[test]
+    fn test_rules_error() {
+        let e = Map::parse("Sab\nyAE", Rules::default()).err().unwrap();
+
+        assert_eq!((e.line, e.column), (2, 2));
+        assert_eq!(e.msg, "expected a height a-z, S or E");
+
+        let e = Map::parse("Sab\nyzb", Rules::default()).err().unwrap();
+        assert_eq!((e.line, e.column), (2, 4));
+        assert_eq!(e.msg, "missing end E");
+
+        let e = Map::parse("SaE\nySE", Rules::default()).err().unwrap();
+        assert_eq!(
+            (e.line, e.column, e.msg.as_str()),
+            (2, 2, "more than one start")
+        );
+
+        let letter = Rules {
+            end: 'z',
+            ..Rules::default()
+        };
+        let e = Map::parse("Sab\nyzE", letter).err().unwrap();
+        assert_eq!((e.line, e.column), (2, 2));
+        assert_eq!(e.msg, "z is both a height and the start or end");
+
+        let same = Rules {
+            end: 'S',
+            ..Rules::default()
+        };
+        let e = Map::parse("Sab\nyzE", same).err().unwrap();
+        assert_eq!(
+            (e.line, e.column, e.msg.as_str()),
+            (1, 1, "S is both start and end")
+        );
+    }
+
+    #[test]
+    fn test_render_gap() {
+        let map = Map::new(read("Sab\nyzE").unwrap());
+        let path = [Point(0, 0), Point(1, 0), Point(1, 2)];
+
+        assert_eq!(map.render(&path, Style::Plain, false), "S?b\nyzE\n");
     }
 }
@@ -1,70 +1,233 @@
-use std::{cmp::Ordering, fmt::Display, iter::Peekable, str::Chars};
+use std::{cmp::Ordering, collections::HashSet, fmt::Display, iter, str::FromStr};
 
 use Paket::*;
 
-#[derive(Debug, PartialEq, Clone)]
+use serde_json::Value as Json;
+
+use crate::parse::ParseError;
+
+#[derive(Debug, Clone)]
 enum Paket {
     List(Vec<Paket>),
     Value(usize),
 }
 
-impl Paket {
-    fn new(line: &str) -> Paket {
-        let mut iter = line.chars().peekable();
-        Paket::builder(&mut iter, ReaderState::Init)
+impl FromStr for Paket {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut reader = Reader { src: s, pos: 0 };
+        let paket = reader.paket()?;
+        reader.skip_whitespace();
+        if reader.pos < s.len() {
+            return Err(reader.error("expected end of paket"));
+        }
+        Ok(paket)
     }
+}
 
-    fn builder(iter: &mut Peekable<Chars>, mut state: ReaderState) -> Paket {
-        let mut root = Vec::new();
+// recursive descent over the bytes of one paket, whitespace between tokens is skipped
+struct Reader<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    // points at the char at the current position
+    fn error(&self, msg: &str) -> ParseError {
+        let len = self.src[self.pos..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+        ParseError::new(13, self.src, &self.src[self.pos..self.pos + len], msg)
+    }
+
+    fn paket(&mut self) -> Result<Paket, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'[') => self.list(),
+            Some(b'0'..=b'9') => self.value(),
+            _ => Err(self.error("expected [ or a number")),
+        }
+    }
+
+    fn list(&mut self) -> Result<Paket, ParseError> {
+        self.pos += 1;
+        let mut list = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(List(list));
+        }
 
         loop {
-            if let Some(c) = iter.next() {
-                match c {
-                    '[' => match state {
-                        ReaderState::Init => {
-                            state = ReaderState::InRoot;
-                        }
-                        ReaderState::InRoot => root.push(Paket::builder(iter, ReaderState::InRoot)),
-                    },
-                    ']' => match state {
-                        ReaderState::Init => panic!(),
-                        ReaderState::InRoot => return List(root),
-                    },
-                    '0'..='9' => match state {
-                        ReaderState::Init => return Value(collect_num(c, iter).parse().unwrap()),
-                        ReaderState::InRoot => {
-                            root.push(Paket::new(&collect_num(c, iter)));
-                        }
-                    },
-                    ',' => (),
-                    _ => panic!("unkown char: {}", c),
+            list.push(self.paket()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(List(list));
                 }
-            } else {
-                break;
+                _ => return Err(self.error("expected , or ]")),
             }
         }
+    }
+
+    fn value(&mut self) -> Result<Paket, ParseError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits = &self.src[start..self.pos];
 
-        fn collect_num(c: char, iter: &mut Peekable<Chars>) -> String {
-            let mut rest_of_num = String::new();
-            while iter.peek().map_or(false, |d| d.is_digit(10)) {
-                rest_of_num.push(iter.next().unwrap());
+        if digits.len() > 1 && digits.starts_with('0') {
+            return Err(ParseError::new(13, self.src, digits, "leading zero"));
+        }
+        digits
+            .parse()
+            .map(Value)
+            .map_err(|_| ParseError::new(13, self.src, digits, "number too large"))
+    }
+}
+
+// pakets separated by any whitespace, so one paket may span several lines like formatted json
+fn read_pakets(input: &str) -> Result<Vec<Paket>, ParseError> {
+    let mut reader = Reader { src: input, pos: 0 };
+    let mut pakets = Vec::new();
+
+    reader.skip_whitespace();
+    while reader.pos < input.len() {
+        pakets.push(reader.paket()?);
+        reader.skip_whitespace();
+    }
+    Ok(pakets)
+}
+
+// pairs are separated by blank lines, a paket itself may span several lines
+fn read_pairs(input: &str) -> Result<Vec<(Paket, Paket)>, ParseError> {
+    input
+        .split("\n\n")
+        .filter(|group| !group.trim().is_empty())
+        .map(|group| {
+            let mut reader = Reader { src: group, pos: 0 };
+            let mut pair = Vec::new();
+
+            reader.skip_whitespace();
+            while reader.pos < group.len() {
+                if pair.len() == 2 {
+                    let e = reader.error("expected a blank line after two pakets");
+                    return Err(e.within(input, group));
+                }
+                pair.push(reader.paket().map_err(|e| e.within(input, group))?);
+                reader.skip_whitespace();
+            }
+
+            let second = pair.pop();
+            match (pair.pop(), second) {
+                (Some(first), Some(second)) => Ok((first, second)),
+                _ => {
+                    let last = group.trim_end().lines().last().unwrap_or(group);
+                    Err(ParseError::missing(13, input, last, "second paket"))
+                }
             }
-            let re = format!("{}{}", c, rest_of_num);
-            re
+        })
+        .collect()
+}
+
+impl From<&Paket> for Json {
+    fn from(paket: &Paket) -> Self {
+        match paket {
+            List(list) => Json::Array(list.iter().map(Json::from).collect()),
+            Value(v) => Json::from(*v),
         }
+    }
+}
 
-        panic!("Paket is not is not closed");
+// json has no source positions, the path of indices like [1][0] leads to the bad value
+#[derive(Debug, PartialEq)]
+struct JsonError {
+    path: String,
+    value: String,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day 13 json {}: expected an array or a non-negative integer \"{}\"",
+            if self.path.is_empty() {
+                "root"
+            } else {
+                &self.path
+            },
+            self.value
+        )
     }
 }
 
-#[derive(Debug)]
-enum ReaderState {
-    Init,
-    InRoot,
+impl std::error::Error for JsonError {}
+
+impl TryFrom<&Json> for Paket {
+    type Error = JsonError;
+
+    fn try_from(json: &Json) -> Result<Self, Self::Error> {
+        match json {
+            Json::Array(list) => Ok(List(
+                list.iter()
+                    .enumerate()
+                    .map(|(i, e)| {
+                        Paket::try_from(e).map_err(|mut e| {
+                            e.path.insert_str(0, &format!("[{}]", i));
+                            e
+                        })
+                    })
+                    .collect::<Result<_, _>>()?,
+            )),
+            Json::Number(n) if n.is_u64() => Ok(Value(n.as_u64().unwrap() as usize)),
+            _ => Err(JsonError {
+                path: String::new(),
+                value: json.to_string(),
+            }),
+        }
+    }
+}
+
+impl Paket {
+    fn write_pretty(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        match self {
+            List(list) if !list.is_empty() => {
+                writeln!(f, "[")?;
+                for (i, e) in list.iter().enumerate() {
+                    write!(f, "{:1$}", "", indent + 2)?;
+                    e.write_pretty(f, indent + 2)?;
+                    writeln!(f, "{}", if i + 1 < list.len() { "," } else { "" })?;
+                }
+                write!(f, "{:1$}]", "", indent)
+            }
+            _ => write!(f, "{}", self),
+        }
+    }
 }
 
+// {:#} prints one element per line, indented like pretty printed json
 impl Display for Paket {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return self.write_pretty(f, 0);
+        }
+
         match self {
             List(list) => write!(
                 f,
@@ -79,112 +242,91 @@ impl Display for Paket {
     }
 }
 
-impl PartialOrd for Paket {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if let (Value(left), Value(right)) = (self, other) {
-            return Some(left.cmp(right));
-        } else if let (List(left), List(right)) = (self, other) {
-            let mut l_iter = left.iter();
-            let mut r_iter = right.iter();
-            loop {
-                let l = l_iter.next();
-                let r = r_iter.next();
-
-                // check if one or both run out
-                if l.is_none() && r.is_none() {
-                    return Some(std::cmp::Ordering::Equal);
-                } else if l.is_some() && r.is_none() {
-                    return Some(std::cmp::Ordering::Greater);
-                } else if l.is_none() && r.is_some() {
-                    return Some(std::cmp::Ordering::Less);
-                }
-
-                // compare current
-                if l != r {
-                    let comp_result = l.unwrap().partial_cmp(r.unwrap()).unwrap();
-                    if let Ordering::Equal = comp_result {
-                        // continue compare
-                    } else {
-                        return Some(comp_result);
-                    }
-                }
-            }
-        } else {
-            if let Value(v) = self {
-                return List(vec![Value(*v)]).partial_cmp(other);
-            } else if let Value(v) = other {
-                return self.partial_cmp(&List(vec![Value(*v)]));
-            }
+// a value compares like a list holding only that value, so 3 and [[3]] are equal
+impl Ord for Paket {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value(left), Value(right)) => left.cmp(right),
+            (List(left), List(right)) => left.iter().cmp(right),
+            (Value(_), List(right)) => iter::once(self).cmp(right),
+            (List(left), Value(_)) => left.iter().cmp(iter::once(other)),
         }
-
-        todo!()
     }
 }
 
-impl Eq for Paket {}
+impl PartialOrd for Paket {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-impl Ord for Paket {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self == other {
-            Ordering::Equal
-        } else {
-            self.partial_cmp(other).unwrap()
-        }
+impl PartialEq for Paket {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
+impl Eq for Paket {}
+
 #[aoc(day13, part1)]
-fn part1(input: &str) -> usize {
-    input
-        .split("\n\n")
-        .map(|pair| {
-            let mut lines = pair.lines();
-            (
-                Paket::new(lines.next().unwrap()),
-                Paket::new(lines.next().unwrap()),
-            )
-        })
+fn part1(input: &str) -> Result<usize, ParseError> {
+    Ok(read_pairs(input)?
+        .iter()
         .enumerate()
         .filter_map(|(i, pair)| if pair.0 < pair.1 { Some(i + 1) } else { None })
-        .sum()
+        .sum())
 }
 
-#[aoc(day13, part2)]
-fn part2(input: &str) -> usize {
-    let div1 = Paket::new("[[2]]");
-    let div2 = Paket::new("[[6]]");
-
-    let mut msg_list: Vec<Paket> = input
-        .split("\n\n")
-        .flat_map(|pair| pair.lines().map(|l| Paket::new(l)))
-        .collect();
-
-    msg_list.push(div1.clone());
-    msg_list.push(div2.clone());
-
-    msg_list.sort();
-
-    let check_pos: Vec<usize> = msg_list
+// 1-based positions the dividers would get in the sorted list, found without sorting.
+// pakets equal to a divider come before it, like a stable sort of the pakets followed by the dividers
+fn divider_positions(pakets: &[Paket], dividers: &[Paket]) -> Vec<usize> {
+    dividers
         .iter()
         .enumerate()
-        .filter_map(|(i, paket)| {
-            if *paket == div1 || *paket == div2 {
-                Some(i + 1)
-            } else {
-                None
-            }
+        .map(|(i, div)| {
+            let before = pakets.iter().filter(|p| *p <= div).count();
+            // equal dividers keep their given order
+            let other_dividers = dividers
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| *other < div || (*other == div && *j < i))
+                .count();
+            before + other_dividers + 1
         })
-        .collect();
+        .collect()
+}
+
+// same tie rule as divider_positions, only pakets written exactly the same are dropped
+fn sorted_with_dividers(pakets: &[Paket], dividers: &[Paket]) -> Vec<Paket> {
+    let mut all: Vec<Paket> = pakets.iter().chain(dividers).cloned().collect();
+    all.sort();
+    let mut seen = HashSet::new();
+    all.retain(|p| seen.insert(p.to_string()));
+    all
+}
 
-    println!("{:?}", check_pos);
+#[aoc(day13, part2)]
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let dividers = read_pakets("[[2]] [[6]]")?;
+    let msg_list = read_pakets(input)?;
+
+    if crate::trace::is_enabled(13) {
+        for paket in sorted_with_dividers(&msg_list, &dividers) {
+            trace!(13, "{}", paket);
+        }
+    }
+
+    let check_pos = divider_positions(&msg_list, &dividers);
+    trace!(13, "divider positions {:?}", check_pos);
 
     // 25200 is to high
-    check_pos.iter().product()
+    Ok(check_pos.iter().product())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::testutil::Rng;
 
     const EXAMPLE: &str = "[1,1,3,1,1]
 [1,1,5,1,1]
@@ -210,76 +352,288 @@ mod test {
 [1,[2,[3,[4,[5,6,7]]]],8,9]
 [1,[2,[3,[4,[5,6,0]]]],8,9]";
 
+    fn p(s: &str) -> Paket {
+        s.parse().unwrap()
+    }
+
+    // == follows the ordering, so 3 == [[3]], the structure is compared on the debug output
+    fn assert_same(left: Paket, right: Paket) {
+        assert_eq!(format!("{:?}", left), format!("{:?}", right));
+    }
+
     #[test]
     fn test_read_paket_empty() {
-        assert_eq!(Paket::new("[]"), List(Vec::new()));
+        assert_same(p("[]"), List(Vec::new()));
+        assert_same(p("[[]]"), List(vec![List(Vec::new())]));
     }
 
     #[test]
     fn test_read_flat_packet() {
-        assert_eq!(
-            Paket::new("[1,2,32]"),
-            List(vec![Value(1), Value(2), Value(32)])
-        );
+        assert_same(p("[1,2,32]"), List(vec![Value(1), Value(2), Value(32)]));
+        assert_same(p("7"), Value(7));
     }
 
     #[test]
     fn test_read_deep_packet() {
-        assert_eq!(
-            Paket::new("[1,[2],[3,4]]"),
+        assert_same(
+            p("[1,[2],[3,4]]"),
             List(vec![
                 Value(1),
                 List(vec![Value(2)]),
-                List(vec![Value(3), Value(4)])
-            ])
+                List(vec![Value(3), Value(4)]),
+            ]),
+        );
+        assert_same(p("[[1]]"), List(vec![List(vec![Value(1)])]));
+    }
+
+    #[test]
+    fn test_read_paket_error() {
+        let e = "[1,[2,x]]".parse::<Paket>().unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (7, "x"));
+        assert_eq!(e.msg, "expected [ or a number");
+
+        let e = "[1,[2]".parse::<Paket>().unwrap_err();
+        assert_eq!((e.offset(), e.msg.as_str()), (6, "expected , or ]"));
+
+        let e = "[1,2]]".parse::<Paket>().unwrap_err();
+        assert_eq!((e.offset(), e.msg.as_str()), (5, "expected end of paket"));
+
+        let e = "[1 2]".parse::<Paket>().unwrap_err();
+        assert_eq!((e.offset(), e.text.as_str()), (3, "2"));
+
+        let e = "[1,02]".parse::<Paket>().unwrap_err();
+        assert_eq!((e.offset(), e.msg.as_str()), (3, "leading zero"));
+
+        let e = "[99999999999999999999999]".parse::<Paket>().unwrap_err();
+        assert_eq!((e.offset(), e.msg.as_str()), (1, "number too large"));
+
+        let e = part1("[1]\n[2]\n\n[3]\n[4,y]").unwrap_err();
+        assert_eq!((e.line, e.column), (5, 4));
+    }
+
+    #[test]
+    fn test_read_whitespace() {
+        assert_same(
+            p(" [ 1, [ ] ,[0,10 ]]  "),
+            List(vec![
+                Value(1),
+                List(vec![]),
+                List(vec![Value(0), Value(10)]),
+            ]),
         );
     }
 
     #[test]
     fn test_cmp_values() {
-        assert!(Paket::new("1") < Paket::new("2"));
-        assert!(Paket::new("5") > Paket::new("2"));
-        assert!(Paket::new("5") == Paket::new("5"));
+        assert!(p("1") < p("2"));
+        assert!(p("5") > p("2"));
+        assert!(p("5") == p("5"));
     }
 
     #[test]
     fn test_cmp_list_neq_len() {
-        assert!(Paket::new("[1]") < Paket::new("[1,2]"));
-        assert!(Paket::new("[0,5,1]") > Paket::new("[0,5]"));
-        assert!(Paket::new("[5,3,1]") == Paket::new("[5,3,1]"));
+        assert!(p("[1]") < p("[1,2]"));
+        assert!(p("[0,5,1]") > p("[0,5]"));
+        assert!(p("[5,3,1]") == p("[5,3,1]"));
     }
 
     #[test]
     fn test_cmp_list_eq_len() {
-        assert!(Paket::new("[1]") < Paket::new("[2]"));
-        assert!(Paket::new("[0,5]") > Paket::new("[0,4]"));
-        assert!(Paket::new("[5,3,1]") == Paket::new("[5,3,1]"));
+        assert!(p("[1]") < p("[2]"));
+        assert!(p("[0,5]") > p("[0,4]"));
+        assert!(p("[5,3,1]") == p("[5,3,1]"));
     }
 
     #[test]
     fn test_cmp_debug() {
-        assert!(Paket::new("[[2]]") < Paket::new("[[[2]],[10,[10,6,8],8,[8,0,10,2],10]]"));
+        assert!(p("[[2]]") < p("[[[2]],[10,[10,6,8],8,[8,0,10,2],10]]"));
     }
 
     #[test]
     fn test_compare_list_to_val() {
         assert_eq!(
-            Paket::new("3").partial_cmp(&Paket::new("[3]")),
+            p("3").partial_cmp(&p("[3]")),
             Some(std::cmp::Ordering::Equal)
         );
         assert_eq!(
-            Paket::new("[3]").partial_cmp(&Paket::new("3")),
+            p("[3]").partial_cmp(&p("3")),
             Some(std::cmp::Ordering::Equal)
         );
     }
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 13)
+        assert_eq!(part1(EXAMPLE), Ok(13))
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 140)
+        assert_eq!(part2(EXAMPLE), Ok(140))
+    }
+
+    #[test]
+    fn test_missing_second() {
+        let e = part1("[1]\n[2]\n\n[3]\n").unwrap_err();
+        assert_eq!(
+            (e.line, e.column, e.msg.as_str()),
+            (4, 4, "missing second paket")
+        );
+
+        // a missing paket must not shift the later pairs
+        let e = part1("[1]\n\n[2]\n[3]\n[4]").unwrap_err();
+        assert_eq!((e.line, e.msg.as_str()), (1, "missing second paket"));
+
+        let e = part1("[1]\n[2]\n\n[3]\n[4]\n[5]").unwrap_err();
+        assert_eq!((e.line, e.column), (6, 1));
+        assert_eq!(e.msg, "expected a blank line after two pakets");
+    }
+
+    #[test]
+    fn test_json() {
+        let paket = p("[1,[2,[]],[[30]]]");
+        let json: Json = serde_json::from_str("[1, [2, []], [[30]]]").unwrap();
+
+        assert_eq!(Json::from(&paket), json);
+        assert_eq!(
+            Paket::try_from(&json).unwrap().to_string(),
+            paket.to_string()
+        );
+
+        let e = Paket::try_from(&serde_json::json!([1, [2, -2]])).unwrap_err();
+        assert_eq!((e.path.as_str(), e.value.as_str()), ("[1][1]", "-2"));
+        assert_eq!(
+            e.to_string(),
+            "day 13 json [1][1]: expected an array or a non-negative integer \"-2\""
+        );
+        let e = Paket::try_from(&serde_json::json!({ "a": 1 })).unwrap_err();
+        assert_eq!(e.path, "");
+    }
+
+    #[test]
+    fn test_pretty() {
+        let paket = p("[1,[2,[]],[[30]]]");
+        let pretty = format!("{:#}", paket);
+
+        assert_eq!(
+            pretty,
+            serde_json::to_string_pretty(&Json::from(&paket)).unwrap()
+        );
+        assert_eq!(format!("{:#}", p("[]")), "[]");
+        assert_eq!(format!("{:#}", p("7")), "7");
+
+        // formatted pakets read back, also when several are in one file
+        let pakets = read_pakets(&format!("{}\n\n{}", pretty, pretty)).unwrap();
+        assert_eq!(pakets.len(), 2);
+        assert_eq!(pakets[1].to_string(), "[1,[2,[]],[[30]]]");
+
+        let smaller = format!("{:#}", p("[1,[2,[]],[[29]]]"));
+        assert_eq!(
+            part1(&format!("{}\n{}\n\n{0}\n{1}", smaller, pretty)),
+            Ok(3)
+        );
+    }
+
+    #[test]
+    fn test_divider_positions() {
+        let pakets = read_pakets(EXAMPLE).unwrap();
+
+        assert_eq!(
+            divider_positions(&pakets, &[p("[[2]]"), p("[[6]]")]),
+            vec![10, 14]
+        );
+        assert_eq!(
+            divider_positions(&pakets, &[p("[[6]]"), p("[]")]),
+            vec![14, 2]
+        );
+        assert_eq!(
+            divider_positions(&pakets, &[p("[2]"), p("2")]),
+            vec![10, 11]
+        );
+        assert_eq!(divider_positions(&[], &[p("5")]), vec![1]);
+
+        // ties go to the pakets
+        assert_eq!(
+            divider_positions(&[p("[[2]]"), p("2"), p("[3]")], &[p("[2]")]),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn test_sorted_with_dividers() {
+        let pakets = read_pakets(EXAMPLE).unwrap();
+        let dividers = [p("[[2]]"), p("[[6]]"), p("[2]")];
+        let sorted = sorted_with_dividers(&pakets, &dividers);
+
+        assert_eq!(sorted.len(), 19);
+        assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(sorted[0].to_string(), "[]");
+        assert_eq!(sorted[9].to_string(), "[[2]]");
+        assert_eq!(sorted[10].to_string(), "[2]");
+
+        // [2] and [[2]] are equal in order but both stay, the exact copy of a divider goes
+        let sorted = sorted_with_dividers(&[p("[[2]]"), p("[2]"), p("[1]")], &[p("[2]")]);
+        assert_eq!(
+            sorted.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+            vec!["[1]", "[[2]]", "[2]"]
+        );
+        assert_eq!(
+            divider_positions(&[p("[[2]]"), p("[1]")], &[p("[2]")]),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn test_nested_equal() {
+        assert_eq!(p("3"), p("[[3]]"));
+        assert!(p("[[]]") < p("0"));
+        assert!(p("[[1],2]") > p("1"));
+    }
+
+    // small values and short lists, so equal parts show up often
+    fn random_paket(rng: &mut Rng, depth: u32) -> Paket {
+        if depth == 0 || rng.below(3) == 0 {
+            Value(rng.below(3) as usize)
+        } else {
+            List(
+                (0..rng.below(4))
+                    .map(|_| random_paket(rng, depth - 1))
+                    .collect(),
+            )
+        }
+    }
+
+    fn random_pakets(seed: u64, n: usize) -> Vec<Paket> {
+        let mut rng = Rng(seed);
+        (0..n).map(|_| random_paket(&mut rng, 4)).collect()
+    }
+
+    #[test]
+    fn test_cmp_antisymmetric() {
+        let pakets = random_pakets(13, 200);
+
+        for a in &pakets {
+            for b in &pakets {
+                assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{} vs {}", a, b);
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cmp_transitive() {
+        let pakets = random_pakets(2022, 60);
+
+        for a in &pakets {
+            for b in &pakets {
+                for c in &pakets {
+                    if a.cmp(b) == b.cmp(c) {
+                        assert_eq!(a.cmp(c), a.cmp(b), "{} {} {}", a, b, c);
+                    }
+                    if a <= b && b <= c {
+                        assert!(a <= c, "{} {} {}", a, b, c);
+                    }
+                }
+            }
+        }
     }
 }
@@ -1,6 +1,10 @@
-use std::{collections::HashSet, fmt::Display, ops::RangeInclusive};
+use std::{collections::HashSet, fmt::Display, fs, io, ops::RangeInclusive, path::Path};
 
-use crate::day09::Point;
+use crate::{
+    geometry::{BoundingBox, Point},
+    grid::{ppm, Grid},
+    parse::{parse_num, ParseError},
+};
 
 fn get_incr_range(a: i32, b: i32) -> RangeInclusive<i32> {
     if a < b {
@@ -10,28 +14,75 @@ fn get_incr_range(a: i32, b: i32) -> RangeInclusive<i32> {
     }
 }
 
-struct Scan {
+// down, down left, down right
+const FALL: [Point; 3] = [Point(0, 1), Point(-1, 1), Point(1, 1)];
+const SOURCE: Point = Point(500, 0);
+
+// how the sand enters and moves, the presets are the two puzzle parts
+#[derive(Debug, Clone)]
+pub struct SandConfig {
+    // several sources drop one grain each in turn
+    pub sources: Vec<Point>,
+    // distance of the floor below the lowest rock, None lets sand fall into the abyss
+    pub floor: Option<i32>,
+    // tried in order, every step has to go down
+    pub fall: Vec<Point>,
+}
+
+impl SandConfig {
+    pub fn abyss() -> Self {
+        Self {
+            sources: vec![SOURCE],
+            floor: None,
+            fall: FALL.to_vec(),
+        }
+    }
+
+    pub fn with_floor() -> Self {
+        Self {
+            floor: Some(2),
+            ..SandConfig::abyss()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Air,
+    Rock,
+    Sand,
+}
+
+pub struct Scan {
     rocks: HashSet<Point>,
-    sand: HashSet<Point>,
+    config: SandConfig,
+    // covers every cell sand can reach above the floor, x is shifted by origin
+    cave: Grid<Cell>,
+    origin: i32,
+    // the way the last grain of each source fell, the next one starts from its top
+    paths: Vec<Vec<Point>>,
+    // sources that are buried or lose their sand to the abyss
+    done: Vec<bool>,
+    turn: usize,
     lowest_rock: i32,
     used_sand: usize,
 }
 
 impl Scan {
-    fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
         let mut rocks = HashSet::new();
 
         for line in input.lines() {
-            let mut coords = line.split(" -> ").map(|str| {
-                let mut nums = str.split(",").map(|s| s.parse().unwrap());
-                Point(nums.next().unwrap(), nums.next().unwrap())
+            let mut coords = line.split(" -> ").map(|str| match str.split_once(',') {
+                Some((x, y)) => Ok(Point(parse_num(14, input, x)?, parse_num(14, input, y)?)),
+                None => Err(ParseError::new(14, input, str, "expected x,y")),
             });
 
-            let mut from = coords.next().expect("No start point");
+            let mut from = coords.next().unwrap()?;
             let mut to_opt = coords.next();
 
-            while to_opt.is_some() {
-                let to = to_opt.unwrap();
+            while let Some(to) = to_opt {
+                let to = to?;
 
                 if from.0 == to.0 {
                     for y in get_incr_range(from.1, to.1) {
@@ -42,7 +93,7 @@ impl Scan {
                         rocks.insert(Point(y, from.1));
                     }
                 } else {
-                    panic!("Diagonal found");
+                    return Err(ParseError::new(14, input, line, "Diagonal found"));
                 }
 
                 from = to;
@@ -50,103 +101,385 @@ impl Scan {
             }
         }
 
-        Scan {
-            lowest_rock: rocks.iter().map(|p| p.1).max().unwrap(),
+        Ok(Scan::from_rocks(rocks))
+    }
+
+    fn from_rocks(rocks: HashSet<Point>) -> Self {
+        let mut scan = Scan {
+            lowest_rock: rocks.iter().map(|p| p.1).max().unwrap_or(0),
             rocks,
+            config: SandConfig::abyss(),
+            cave: Grid::new(0, 0, Cell::Air),
+            origin: 0,
+            paths: Vec::new(),
+            done: Vec::new(),
+            turn: 0,
             used_sand: 0,
-            sand: HashSet::new(),
+        };
+        scan.configure(SandConfig::abyss())
+            .expect("the presets are valid");
+        scan
+    }
+
+    // removes all sand and sizes the cave for the config
+    fn configure(&mut self, config: SandConfig) -> Result<(), String> {
+        if config.fall.iter().any(|d| d.1 <= 0) {
+            return Err("sand has to fall down in every step".to_string());
+        }
+        if config.floor.is_some_and(|floor| floor < 1) {
+            return Err("the floor has to be below the lowest rock".to_string());
+        }
+        let max_dx = config.fall.iter().map(|d| d.0.abs()).max().unwrap_or(0);
+        let max_dy = config.fall.iter().map(|d| d.1).max().unwrap_or(1);
+        let height = match config.floor {
+            Some(floor) => self.lowest_rock + floor,
+            // one more step to see a grain passing the lowest rock
+            None => self.lowest_rock + 1 + max_dy,
+        };
+        if let Some(source) = config.sources.iter().find(|s| s.1 < 0 || s.1 >= height) {
+            return Err(format!("source {:?} is outside of the cave", source));
+        }
+
+        // a grain moves at most max_dx columns per row it falls
+        let spread = Point(max_dx * height, 0);
+        let mut bb = BoundingBox::new(SOURCE, SOURCE);
+        for source in &config.sources {
+            bb.extend(&(*source - spread));
+            bb.extend(&(*source + spread));
         }
+        self.rocks.iter().for_each(|p| bb.extend(p));
+
+        self.cave = Grid::new(bb.width() as usize, height.max(0) as usize, Cell::Air);
+        self.origin = bb.min.0;
+        for rock in self.rocks.clone() {
+            self.set(rock, Cell::Rock);
+        }
+        self.paths = vec![Vec::new(); config.sources.len()];
+        self.done = vec![false; config.sources.len()];
+        self.turn = 0;
+        self.used_sand = 0;
+        self.config = config;
+        Ok(())
     }
 
-    fn fill(&mut self) {
-        while self.add_sand(false) {
-            self.used_sand += 1;
+    // with the floor the sand forms a triangle under the source, minus every cell
+    // whose three cells above are all blocked, counted row by row without dropping grains
+    fn count_reachable(&self) -> usize {
+        let floor = self.lowest_rock + 2;
+        let left = SOURCE.0 - floor;
+        let mut row = vec![false; 2 * floor as usize + 1];
+        row[floor as usize] = !self.rocks.contains(&SOURCE);
+        let mut count = row.iter().filter(|r| **r).count();
+
+        for y in SOURCE.1 + 1..floor {
+            row = (0..row.len())
+                .map(|i| {
+                    let from_above = row[i.saturating_sub(1)..(i + 2).min(row.len())]
+                        .iter()
+                        .any(|r| *r);
+                    from_above && !self.rocks.contains(&Point(left + i as i32, y))
+                })
+                .collect();
+            count += row.iter().filter(|r| **r).count();
+        }
+        count
+    }
+
+    fn cell(&self, p: Point) -> Option<Cell> {
+        self.cave.get(Point(p.0 - self.origin, p.1)).copied()
+    }
+
+    fn set(&mut self, p: Point, cell: Cell) {
+        if let Some(c) = self.cave.get_mut(Point(p.0 - self.origin, p.1)) {
+            *c = cell;
         }
     }
 
+    fn fill(&mut self) {
+        self.fill_with(SandConfig::abyss())
+            .expect("the presets are valid");
+    }
+
     fn fill_with_floor(&mut self) {
-        while self.add_sand(true) {
+        self.fill_with(SandConfig::with_floor())
+            .expect("the presets are valid");
+    }
+
+    // drops sand until every source is done, returns the number of grains at rest
+    pub fn fill_with(&mut self, config: SandConfig) -> Result<usize, String> {
+        self.configure(config)?;
+        self.drop_all(|scan| trace!(14, "{}", scan));
+        Ok(self.used_sand)
+    }
+
+    // like fill_with, the recorder sees the cave after every grain
+    pub fn fill_recorded(
+        &mut self,
+        config: SandConfig,
+        recorder: &mut Recorder,
+    ) -> Result<usize, String> {
+        self.configure(config)?;
+        recorder.start(self);
+        self.drop_all(|scan| recorder.grain(scan));
+        recorder.finish(self);
+        Ok(self.used_sand)
+    }
+
+    fn drop_all(&mut self, mut on_grain: impl FnMut(&Scan)) {
+        while self.add_sand() {
             self.used_sand += 1;
-            //println!("{}", self);
+            on_grain(self);
         }
     }
-    fn add_sand(&mut self, has_floor: bool) -> bool {
-        let start = Point(500, 0);
-        let mut sand_pos = start.clone();
 
-        loop {
-            let next_pos = vec![Point(0, 1), Point(-1, 1), Point(1, 1)]
+    // drops one grain from the next source in turn, false once all sources are done
+    fn add_sand(&mut self) -> bool {
+        while self.done.contains(&false) {
+            let source = self.turn;
+            self.turn = (self.turn + 1) % self.done.len();
+
+            if !self.done[source] {
+                if self.drop_from(source) {
+                    return true;
+                }
+                self.done[source] = true;
+            }
+        }
+        false
+    }
+
+    // false if the source is blocked or the grain falls into the abyss
+    fn drop_from(&mut self, source: usize) -> bool {
+        let mut path = std::mem::take(&mut self.paths[source]);
+        // sand of other sources may have landed on the way since
+        if let Some(blocked) = path.iter().position(|p| self.cell(*p) != Some(Cell::Air)) {
+            path.truncate(blocked);
+        }
+        if path.is_empty() {
+            let start = self.config.sources[source];
+            if self.cell(start) != Some(Cell::Air) {
+                return false;
+            }
+            path.push(start);
+        }
+
+        let rested = loop {
+            let sand_pos = *path.last().unwrap();
+            // below the grid is the floor
+            let next_pos = self
+                .config
+                .fall
                 .iter()
-                .map(|p| sand_pos.clone() + p.clone())
-                .filter(|p| p.1 < self.lowest_rock + 2)
-                .find(|p| !self.rocks.contains(p) && !self.sand.contains(p));
+                .map(|p| sand_pos + *p)
+                .find(|p| self.cell(*p) == Some(Cell::Air));
 
-            //            println!("from {:?} to {:?}", sand_pos, next_pos);
+            trace!(14, "from {:?} to {:?}", sand_pos, next_pos);
 
             match next_pos {
-                Some(pos) => {
-                    if !has_floor && pos.1 > self.lowest_rock {
-                        return false;
-                    } else {
-                        sand_pos = pos;
-                    }
+                Some(pos) if self.config.floor.is_none() && pos.1 > self.lowest_rock => {
+                    break false
                 }
+                Some(pos) => path.push(pos),
                 None => {
-                    if sand_pos == start {
-                        self.sand.insert(start);
-                        return false;
-                    } else {
-                        self.sand.insert(sand_pos);
-                        return true;
-                    }
+                    self.set(sand_pos, Cell::Sand);
+                    path.pop();
+                    break true;
                 }
             }
-        }
+        };
+
+        self.paths[source] = path;
+        rested
     }
 }
 
 impl Display for Scan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let min_y = self.rocks.iter().map(|p| p.0).min().unwrap_or(0);
-        let max_y = self.rocks.iter().map(|p| p.0).max().unwrap_or(0);
-        let mut re = String::new();
-
-        for row in 0..=self.rocks.iter().map(|p| p.1).max().unwrap_or(0) {
-            for pos in min_y..=max_y {
-                if self.sand.contains(&Point(pos, row)) {
-                    re.push('o')
-                } else if row == 0 && pos == 500 {
-                    re.push('+')
-                } else {
-                    match self.rocks.contains(&Point(pos, row)) {
-                        true => re.push('#'),
-                        false => re.push('.'),
-                    }
-                }
-            }
+        let bb = viewport(&self.cave, self.origin, &self.config.sources);
+        write!(
+            f,
+            "{}",
+            draw(&self.cave, self.origin, &self.config.sources, bb)
+        )
+    }
+}
+
+// everything that is not air, together with the sources
+fn viewport(cave: &Grid<Cell>, origin: i32, sources: &[Point]) -> BoundingBox {
+    let mut bb = BoundingBox::new(SOURCE, SOURCE);
+    sources.iter().for_each(|p| bb.extend(p));
+    cave.iter()
+        .filter(|(_, c)| **c != Cell::Air)
+        .for_each(|(p, _)| bb.extend(&Point(p.0 + origin, p.1)));
+    // the drawing always starts at the top of the cave
+    bb.min.1 = 0;
+    bb
+}
+
+fn draw(cave: &Grid<Cell>, origin: i32, sources: &[Point], bb: BoundingBox) -> String {
+    let mut re = String::new();
+
+    for p in bb.points() {
+        match cave.get(Point(p.0 - origin, p.1)) {
+            Some(Cell::Sand) => re.push('o'),
+            _ if sources.contains(&p) => re.push('+'),
+            Some(Cell::Rock) => re.push('#'),
+            _ => re.push('.'),
+        }
+        if p.0 == bb.max.0 {
             re.push('\n');
         }
-        write!(f, "{}", re.trim_end())
+    }
+    re.trim_end().to_string()
+}
+
+// snapshots of the cave every few grains, all frames use the viewport of the last one.
+// a frame only keeps the cells that changed since the one before, so long runs stay small
+pub struct Recorder {
+    every: usize,
+    origin: i32,
+    sources: Vec<Point>,
+    // the cave before the first grain and after the last capture
+    first: Grid<Cell>,
+    last: Grid<Cell>,
+    frames: Vec<(usize, Vec<(Point, Cell)>)>,
+}
+
+impl Recorder {
+    pub fn new(every: usize) -> Self {
+        Self {
+            every: every.max(1),
+            origin: 0,
+            sources: Vec::new(),
+            first: Grid::new(0, 0, Cell::Air),
+            last: Grid::new(0, 0, Cell::Air),
+            frames: Vec::new(),
+        }
+    }
+
+    fn start(&mut self, scan: &Scan) {
+        self.origin = scan.origin;
+        self.sources = scan.config.sources.clone();
+        self.first = scan.cave.clone();
+        self.last = scan.cave.clone();
+        self.frames.clear();
+    }
+
+    fn grain(&mut self, scan: &Scan) {
+        if scan.used_sand.is_multiple_of(self.every) {
+            self.capture(scan);
+        }
+    }
+
+    fn finish(&mut self, scan: &Scan) {
+        if self.frames.last().is_none_or(|(n, _)| *n != scan.used_sand) {
+            self.capture(scan);
+        }
+    }
+
+    fn capture(&mut self, scan: &Scan) {
+        let mut changed = Vec::new();
+        for (p, cell) in scan.cave.iter() {
+            if self.last[p] != *cell {
+                self.last[p] = *cell;
+                changed.push((p, *cell));
+            }
+        }
+        self.frames.push((scan.used_sand, changed));
+    }
+
+    fn viewport(&self) -> BoundingBox {
+        viewport(&self.last, self.origin, &self.sources)
+    }
+
+    // rebuilds the caves one frame after the other
+    fn replay(&self, mut f: impl FnMut(usize, &Grid<Cell>)) {
+        let mut cave = self.first.clone();
+        for (n, changed) in &self.frames {
+            for (p, cell) in changed {
+                cave[*p] = *cell;
+            }
+            f(*n, &cave);
+        }
+    }
+
+    pub fn ascii_log(&self) -> String {
+        let bb = self.viewport();
+        let mut log = Vec::new();
+        self.replay(|n, cave| {
+            log.push(format!(
+                "grains: {}\n{}\n",
+                n,
+                draw(cave, self.origin, &self.sources, bb)
+            ))
+        });
+        log.join("\n")
+    }
+
+    // binary P6 image, every cell becomes a square of scale pixels
+    fn ppm_frame(&self, cave: &Grid<Cell>, bb: BoundingBox, scale: usize) -> Vec<u8> {
+        let (width, height) = (bb.width() as usize * scale, bb.height() as usize * scale);
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let p = bb.min + Point((x / scale) as i32, (y / scale) as i32);
+                match cave.get(Point(p.0 - self.origin, p.1)) {
+                    Some(Cell::Sand) => [230, 190, 80],
+                    _ if self.sources.contains(&p) => [220, 40, 40],
+                    Some(Cell::Rock) => [110, 110, 110],
+                    _ => [20, 20, 40],
+                }
+            });
+        ppm(width, height, pixels)
+    }
+
+    pub fn ppm_frames(&self, scale: usize) -> Vec<Vec<u8>> {
+        let bb = self.viewport();
+        let mut images = Vec::new();
+        self.replay(|_, cave| images.push(self.ppm_frame(cave, bb, scale)));
+        images
+    }
+
+    // frame_0000.ppm, frame_0001.ppm, ... in dir, each written as soon as it is drawn
+    pub fn write_ppm(&self, dir: &Path, scale: usize) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let bb = self.viewport();
+        let mut i = 0;
+        let mut result = Ok(());
+        self.replay(|_, cave| {
+            if result.is_ok() {
+                let file = dir.join(format!("frame_{:04}.ppm", i));
+                result = fs::write(file, self.ppm_frame(cave, bb, scale));
+                i += 1;
+            }
+        });
+        result
     }
 }
 
 #[aoc(day14, part1)]
-fn part1(input: &str) -> usize {
-    let mut wall = Scan::new(input);
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let mut wall = Scan::new(input)?;
     wall.fill();
-    wall.used_sand
+    Ok(wall.used_sand)
 }
 
 #[aoc(day14, part2)]
-fn part2(input: &str) -> usize {
-    let mut wall = Scan::new(input);
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let mut wall = Scan::new(input)?;
     wall.fill_with_floor();
-    wall.used_sand + 1
+    Ok(wall.used_sand)
+}
+
+#[aoc(day14, part2, triangle)]
+fn part2_triangle(input: &str) -> Result<usize, ParseError> {
+    Ok(Scan::new(input)?.count_reachable())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::path::bfs;
 
     const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9";
@@ -154,7 +487,7 @@ mod test {
     #[test]
     fn test_reader() {
         assert_eq!(
-            Scan::new(EXAMPLE).to_string(),
+            Scan::new(EXAMPLE).unwrap().to_string(),
             "......+...
 ..........
 ..........
@@ -170,11 +503,221 @@ mod test {
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 24)
+        assert_eq!(part1(EXAMPLE), Ok(24))
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 93)
+        assert_eq!(part2(EXAMPLE), Ok(93))
+    }
+
+    #[test]
+    fn test_reader_error() {
+        let e = Scan::new("498,4 -> 498,6\n503,4 -> 502,x4").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 14, "x4"));
+
+        let e = Scan::new("498,4 -> 498,6\n503,4 -> 502,5").err().unwrap();
+        assert_eq!((e.line, e.column, e.msg.as_str()), (2, 1, "Diagonal found"));
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut wall = Scan::new(EXAMPLE).unwrap();
+        for _ in 0..5 {
+            wall.add_sand();
+        }
+
+        assert_eq!(
+            wall.to_string().lines().skip(7).collect::<Vec<_>>(),
+            vec!["......o.#.", "....oooo#.", "#########."]
+        );
+        assert!(!wall.paths[0].is_empty());
+    }
+
+    #[test]
+    fn test_fill_with_floor() {
+        let mut wall = Scan::new(EXAMPLE).unwrap();
+        wall.fill_with_floor();
+
+        assert_eq!(wall.cell(SOURCE), Some(Cell::Sand));
+        assert_eq!(wall.cell(Point(490, 10)), Some(Cell::Sand));
+        assert_eq!(wall.cell(Point(489, 10)), Some(Cell::Air));
+        assert!(!wall.add_sand());
+    }
+
+    #[test]
+    fn test_presets() {
+        let mut wall = Scan::new(EXAMPLE).unwrap();
+
+        assert_eq!(wall.fill_with(SandConfig::abyss()), Ok(24));
+        assert_eq!(wall.fill_with(SandConfig::with_floor()), Ok(93));
+        // refilling starts from an empty cave again
+        assert_eq!(wall.fill_with(SandConfig::abyss()), Ok(24));
+    }
+
+    #[test]
+    fn test_config() {
+        let mut wall = Scan::new(EXAMPLE).unwrap();
+
+        // straight down only, so each column fills up to the first rock
+        let straight = SandConfig {
+            fall: vec![Point(0, 1)],
+            ..SandConfig::with_floor()
+        };
+        assert_eq!(wall.fill_with(straight), Ok(9));
+
+        let mirrored = SandConfig {
+            fall: vec![Point(0, 1), Point(1, 1), Point(-1, 1)],
+            ..SandConfig::abyss()
+        };
+        assert_eq!(wall.fill_with(mirrored), Ok(27));
+
+        let deep = SandConfig {
+            floor: Some(4),
+            ..SandConfig::with_floor()
+        };
+        // two more rows of the triangle, 23 and 25 cells wide, without the 5 and 3 cells
+        // still in the shadow of the rock line 494..=502 at y 9
+        assert_eq!(wall.fill_with(deep), Ok(93 + (23 - 5) + (25 - 3)));
+    }
+
+    #[test]
+    fn test_config_error() {
+        let mut wall = Scan::new(EXAMPLE).unwrap();
+
+        let up = SandConfig {
+            fall: vec![Point(0, 1), Point(1, 0)],
+            ..SandConfig::abyss()
+        };
+        assert_eq!(
+            wall.fill_with(up),
+            Err("sand has to fall down in every step".to_string())
+        );
+
+        let no_floor = SandConfig {
+            floor: Some(0),
+            ..SandConfig::with_floor()
+        };
+        assert!(wall.fill_with(no_floor).is_err());
+
+        for source in [Point(500, -1), Point(500, 11)] {
+            let outside = SandConfig {
+                sources: vec![source],
+                ..SandConfig::with_floor()
+            };
+            assert_eq!(
+                wall.fill_with(outside),
+                Err(format!("source {:?} is outside of the cave", source))
+            );
+        }
+    }
+
+    #[test]
+    fn test_sources() {
+        let mut wall = Scan::new(EXAMPLE).unwrap();
+        let two = SandConfig {
+            sources: vec![Point(500, 0), Point(497, 0)],
+            ..SandConfig::with_floor()
+        };
+        let both = wall.fill_with(two.clone()).unwrap();
+
+        assert_eq!(wall.cell(Point(497, 0)), Some(Cell::Sand));
+        assert_eq!(wall.cell(Point(500, 0)), Some(Cell::Sand));
+        // every cell below one of the sources fills up
+        let floor = wall.lowest_rock + 2;
+        let reachable = bfs(
+            two.sources.clone(),
+            |p: &Point| {
+                FALL.iter()
+                    .map(|d| *p + *d)
+                    .filter(|n| n.1 < floor && !wall.rocks.contains(n))
+                    .collect::<Vec<_>>()
+            },
+            |_| false,
+        );
+        assert_eq!(both, reachable.distances().len());
+        assert_eq!(both, 124);
+
+        // the sources take turns, 500 piles up on the long rock, 497 fills the cup and spills left
+        wall.configure(two).unwrap();
+        let mut landed = Vec::new();
+        for _ in 0..4 {
+            assert!(wall.add_sand());
+            let sand = wall.cave.iter().filter(|(_, c)| **c == Cell::Sand);
+            let new = sand
+                .map(|(p, _)| Point(p.0 + wall.origin, p.1))
+                .find(|p| !landed.contains(p))
+                .unwrap();
+            landed.push(new);
+        }
+        assert_eq!(
+            landed,
+            vec![Point(500, 8), Point(497, 5), Point(499, 8), Point(495, 8)]
+        );
+    }
+
+    #[test]
+    fn test_display_spread() {
+        let mut wall = Scan::new(EXAMPLE).unwrap();
+        wall.fill_with_floor();
+        let out = wall.to_string();
+
+        assert_eq!(out.lines().count(), 11);
+        assert_eq!(out.lines().next(), Some("..........o.........."));
+        assert_eq!(out.lines().last(), Some("ooooo.......ooooooooo"));
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut wall = Scan::new(EXAMPLE).unwrap();
+        let mut recorder = Recorder::new(10);
+        assert_eq!(
+            wall.fill_recorded(SandConfig::with_floor(), &mut recorder),
+            Ok(93)
+        );
+
+        // every 10 grains and the last one
+        assert_eq!(recorder.frames.len(), 10);
+        // one grain comes to rest per grain dropped
+        assert_eq!(recorder.frames[0].1.len(), 10);
+        assert_eq!(recorder.frames[9].1.len(), 3);
+        let log = recorder.ascii_log();
+        assert!(log.starts_with("grains: 10\n..........+..........\n"));
+        assert!(log.contains("grains: 93\n..........o..........\n"));
+
+        let images = recorder.ppm_frames(2);
+        assert_eq!(images.len(), 10);
+        let header = b"P6\n42 22\n255\n";
+        assert!(images[0].starts_with(header));
+        assert_eq!(images[0].len(), header.len() + 42 * 22 * 3);
+
+        // one directory per process, so parallel runs do not clean up each other's frames
+        let dir = std::env::temp_dir().join(format!("aoc_day14_frames_{}", std::process::id()));
+        recorder.write_ppm(&dir, 1).unwrap();
+        assert!(dir.join("frame_0009.ppm").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_triangle() {
+        assert_eq!(part2_triangle(EXAMPLE), Ok(93));
+
+        let caves = [
+            EXAMPLE,
+            "500,2 -> 500,2",
+            "499,1 -> 501,1",
+            "490,5 -> 510,5\n495,3 -> 505,3",
+            "480,12 -> 520,12\n500,4 -> 500,8 -> 503,8\n470,6 -> 478,6",
+            "460,30 -> 540,30\n497,3 -> 499,3\n501,3 -> 503,3\n500,10 -> 500,20",
+        ];
+        for cave in caves {
+            let mut wall = Scan::new(cave).unwrap();
+            assert_eq!(
+                wall.count_reachable(),
+                wall.fill_with(SandConfig::with_floor()).unwrap(),
+                "{}",
+                cave
+            );
+        }
     }
 }
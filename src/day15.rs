@@ -1,9 +1,90 @@
-use crate::day09::Point;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    geometry::{BoundingBox, Point},
+    grid,
+    parse::{parse_num, ParseError},
+    path::bfs,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 
+// what the solvers look for, an optional first line like
+// "# row=10 region=0,0..20,20 factor=4000000" overrides the puzzle values
+#[derive(Debug, PartialEq, Clone)]
+pub struct BeaconQuery {
+    pub row: i32,
+    pub region: BoundingBox,
+    // tuning frequency is x * factor + y
+    pub factor: i64,
+}
+
+impl Default for BeaconQuery {
+    fn default() -> Self {
+        Self {
+            row: 2_000_000,
+            region: BoundingBox::new(Point(0, 0), Point(4_000_000, 4_000_000)),
+            factor: 4_000_000,
+        }
+    }
+}
+
+impl BeaconQuery {
+    fn from_header(input: &str, line: &str) -> Result<Self, ParseError> {
+        let mut query = BeaconQuery::default();
+
+        for setting in line.trim_start_matches('#').split_whitespace() {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| ParseError::new(15, input, setting, "expected key=value"))?;
+            match key {
+                "row" => query.row = parse_num(15, input, value)?,
+                "region" => query.region = read_region(input, value)?,
+                "factor" => query.factor = parse_num(15, input, value)?,
+                _ => return Err(ParseError::new(15, input, key, "unknown setting")),
+            }
+        }
+        Ok(query)
+    }
+
+    pub fn frequency(&self, p: Point) -> i64 {
+        self.factor * i64::from(p.0) + i64::from(p.1)
+    }
+}
+
+// x,y..x,y with both corners inside
+fn read_region(input: &str, value: &str) -> Result<BoundingBox, ParseError> {
+    let point = |part: &str| match part.split_once(',') {
+        Some((x, y)) => Ok(Point(parse_num(15, input, x)?, parse_num(15, input, y)?)),
+        None => Err(ParseError::new(15, input, part, "expected x,y")),
+    };
+    match value.split_once("..") {
+        Some((min, max)) => Ok(BoundingBox::new(point(min)?, point(max)?)),
+        None => Err(ParseError::new(15, input, value, "expected x,y..x,y")),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    query: BeaconQuery,
+    sensors: Vec<Sensor>,
+}
+
 #[aoc_generator(day15)]
-pub fn read(input: &str) -> Vec<Sensor> {
+pub fn read_report(input: &str) -> Result<Report, ParseError> {
+    match input.split_once('\n') {
+        Some((header, rest)) if header.starts_with('#') => Ok(Report {
+            query: BeaconQuery::from_header(input, header)?,
+            sensors: read(rest).map_err(|e| e.within(input, rest))?,
+        }),
+        _ => Ok(Report {
+            query: BeaconQuery::default(),
+            sensors: read(input)?,
+        }),
+    }
+}
+
+pub fn read(input: &str) -> Result<Vec<Sensor>, ParseError> {
     lazy_static! {
         static ref RE: Regex = Regex::new(
             r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)"
@@ -14,23 +95,15 @@ pub fn read(input: &str) -> Vec<Sensor> {
     return input
         .lines()
         .map(|line| {
-            let cap_vec: Vec<i32> = RE
-                .captures_iter(line)
-                .flat_map(|caps| {
-                    caps.iter()
-                        .skip(1)
-                        .map(|cap| cap.unwrap().as_str().parse::<i32>().unwrap())
-                        .collect::<Vec<i32>>()
-                        .clone()
-                })
-                .collect();
-
-            let mut cap = cap_vec.into_iter();
+            let caps = RE
+                .captures(line)
+                .ok_or_else(|| ParseError::new(15, input, line, "expected a sensor"))?;
+            let num = |i| parse_num(15, input, &line[caps.get(i).unwrap().range()]);
 
-            Sensor {
-                pos: Point(cap.next().unwrap(), cap.next().unwrap()),
-                closest_beacon: Point(cap.next().unwrap(), cap.next().unwrap()),
-            }
+            Ok(Sensor {
+                pos: Point(num(1)?, num(2)?),
+                closest_beacon: Point(num(3)?, num(4)?),
+            })
         })
         .collect();
 }
@@ -57,79 +130,306 @@ impl Sensor {
     }
 }
 
-impl Point {
-    fn man_dist(&self, other: &Point) -> i32 {
-        (self.0 - other.0).abs() + (self.1 - other.1).abs()
+// sorted, disjoint ranges the sensors cover on a row, touching ranges are joined
+fn merged_ranges(sensors: &[Sensor], l_num: i32) -> Vec<(i32, i32)> {
+    let mut ranges: Vec<(i32, i32)> = sensors
+        .iter()
+        .filter_map(|s| s.get_scanned_on_line(l_num))
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(i32, i32)> = Vec::new();
+    for r in ranges {
+        match merged.last_mut() {
+            Some(last) if r.0 <= last.1 + 1 => last.1 = last.1.max(r.1),
+            _ => merged.push(r),
+        }
     }
+    merged
 }
 
 fn get_num_non_becon_spaces(sensors: &[Sensor], l_num: i32) -> i32 {
-    if sensors.is_empty() {
-        return 0;
-    }
+    let ranges = merged_ranges(sensors, l_num);
+    let covered: i32 = ranges.iter().map(|r| r.1 - r.0 + 1).sum();
 
-    let ranges: Vec<(i32, i32)> = sensors
+    // every beacon lies in the range of its sensor
+    let beacons: HashSet<Point> = sensors
         .iter()
-        .filter_map(|s| s.get_scanned_on_line(l_num))
+        .map(|s| s.closest_beacon)
+        .filter(|b| b.1 == l_num)
         .collect();
 
-    let min = ranges.iter().map(|t| t.0).min().unwrap();
-    let max = ranges.iter().map(|t| t.1).max().unwrap();
-
-    let mut non_becon = 0;
-
-    for i in min..=max {
-        // in some range
-        if ranges.iter().any(|t| t.0 <= i && t.1 >= i)
-        // not a known beacon
-            && !sensors.iter().any(|s| s.closest_beacon == Point(i, l_num))
-        {
-            non_becon += 1;
-        }
-    }
-
-    return non_becon;
+    covered - beacons.len() as i32
 }
 
 #[aoc(day15, part1)]
-fn part1(input: &[Sensor]) -> i32 {
-    get_num_non_becon_spaces(input, 2000000)
+fn part1(input: &Report) -> i32 {
+    get_num_non_becon_spaces(&input.sensors, input.query.row)
 }
 
-fn find_hole_in_square(sensors: &[Sensor], square_size: i32) -> i64 {
-    for y in 0..=square_size {
-        let mut x = 0;
-        while x <= square_size {
+fn find_hole(sensors: &[Sensor], region: BoundingBox) -> Option<Point> {
+    for y in region.min.1..=region.max.1 {
+        let mut x = region.min.0;
+        while x <= region.max.0 {
             if let Some(r) = sensors
                 .iter()
                 .filter_map(|s| s.get_scanned_on_line(y))
                 .find(|r| x >= r.0 && x <= r.1)
             {
-                //println!("range {:?}", r);
+                trace!(15, "range {:?}", r);
                 // set to end of range
                 x = r.1 + 1;
             } else {
-                //println!("Found P({},{})", x, y);
-                let bx: i64 = x.into();
-                let by: i64 = y.into();
-                return 4000000 * bx + by;
+                trace!(15, "found P({},{})", x, y);
+                return Some(Point(x, y));
             }
         }
     }
-    panic!("No hole found");
+    None
 }
 
 #[aoc(day15, part2)]
-fn part2(input: &[Sensor]) -> i64 {
+fn part2(input: &Report) -> i64 {
     // 106047218 to low
     // 11246012445539524 to high
     // 424188978047218 to high
-    find_hole_in_square(input, 4_000_000)
+    let hole = find_hole(&input.sensors, input.query.region).expect("No hole found");
+    input.query.frequency(hole)
+}
+
+// uncovered cells of one row, from and to included
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Gap {
+    pub y: i32,
+    pub from: i32,
+    pub to: i32,
+}
+
+impl Gap {
+    pub fn cells(&self) -> impl Iterator<Item = Point> {
+        let y = self.y;
+        (self.from..=self.to).map(move |x| Point(x, y))
+    }
+}
+
+// the parts of a region row no sensor covers
+fn gaps(sensors: &[Sensor], region: BoundingBox, y: i32) -> Vec<Gap> {
+    let mut gaps = Vec::new();
+    let mut x = region.min.0;
+    for r in merged_ranges(sensors, y) {
+        if r.0 > x {
+            gaps.push(Gap {
+                y,
+                from: x,
+                to: (r.0 - 1).min(region.max.0),
+            });
+        }
+        x = x.max(r.1 + 1);
+        if x > region.max.0 {
+            return gaps;
+        }
+    }
+    gaps.push(Gap {
+        y,
+        from: x,
+        to: region.max.0,
+    });
+    gaps
+}
+
+// In rotated coordinates u = x + y, v = x - y every diamond is an axis aligned square, the
+// cells just out of range lie on the lines u = su ± (r + 1) and v = sv ± (r + 1).
+// The top left cell of every uncovered area is where such lines or the region edges cross,
+// so only the rows of those points are scanned. Each gap found there is grown row by row to
+// its whole area, gaps that touch diagonally belong together.
+fn find_holes(sensors: &[Sensor], region: BoundingBox) -> Vec<Gap> {
+    let mut us = Vec::new();
+    let mut vs = Vec::new();
+    for s in sensors {
+        let (u, v, r) = (s.pos.0 + s.pos.1, s.pos.0 - s.pos.1, s.scan_range() + 1);
+        us.extend([u - r, u + r]);
+        vs.extend([v - r, v + r]);
+    }
+
+    let (min, max) = (region.min, region.max);
+    let mut rows = vec![min.1];
+    for u in &us {
+        for v in &vs {
+            // lines of different parity cross between two rows, both can hold the corner
+            rows.extend([(u - v).div_euclid(2), (u - v + 1).div_euclid(2)]);
+        }
+    }
+    for x in [min.0, max.0] {
+        rows.extend(us.iter().map(|u| u - x));
+        rows.extend(vs.iter().map(|v| x - v));
+    }
+    rows.retain(|y| (min.1..=max.1).contains(y));
+    rows.sort_unstable();
+    rows.dedup();
+
+    let seeds = rows.iter().flat_map(|y| gaps(sensors, region, *y));
+    let search = bfs(
+        seeds,
+        |g: &Gap| {
+            [g.y - 1, g.y + 1]
+                .into_iter()
+                .filter(|y| (min.1..=max.1).contains(y))
+                .flat_map(|y| gaps(sensors, region, y))
+                .filter(|n| n.from <= g.to + 1 && g.from <= n.to + 1)
+                .collect::<Vec<_>>()
+        },
+        |_| false,
+    );
+
+    let mut holes: Vec<Gap> = search.distances().keys().copied().collect();
+    holes.sort_by_key(|g| (g.y, g.from));
+    holes
+}
+
+#[aoc(day15, part2, perimeter)]
+fn part2_perimeter(input: &Report) -> i64 {
+    let holes = find_holes(&input.sensors, input.query.region);
+    trace!(15, "uncovered {:?}", holes);
+
+    let hole = holes.first().expect("No hole found");
+    input.query.frequency(Point(hole.from, hole.y))
+}
+
+// part of the plane to draw, each char or pixel stands for a scale x scale block of cells
+#[derive(Debug, Clone, Copy)]
+pub struct View {
+    pub window: BoundingBox,
+    pub scale: i32,
+}
+
+const PALETTE: [[f32; 3]; 6] = [
+    [70.0, 130.0, 230.0],
+    [230.0, 160.0, 50.0],
+    [160.0, 90.0, 220.0],
+    [60.0, 200.0, 200.0],
+    [220.0, 90.0, 120.0],
+    [140.0, 200.0, 80.0],
+];
+
+impl View {
+    pub fn new(window: BoundingBox, scale: i32) -> Self {
+        Self {
+            window,
+            scale: scale.max(1),
+        }
+    }
+
+    fn size(&self) -> (i32, i32) {
+        let blocks = |len: i32| (len + self.scale - 1) / self.scale;
+        (blocks(self.window.width()), blocks(self.window.height()))
+    }
+
+    // top left cell of each block
+    fn cell(&self, x: i32, y: i32) -> Point {
+        self.window.min + Point(x, y) * self.scale
+    }
+
+    // block holding the cell
+    fn block(&self, p: Point) -> Point {
+        Point(
+            (p.0 - self.window.min.0).div_euclid(self.scale),
+            (p.1 - self.window.min.1).div_euclid(self.scale),
+        )
+    }
+
+    // like the puzzle drawings, blocks show their top left cell
+    // unless a sensor or beacon lies anywhere in them
+    pub fn ascii(&self, sensors: &[Sensor]) -> String {
+        let (width, height) = self.size();
+        let mut marks = HashMap::new();
+        for s in sensors {
+            marks.entry(self.block(s.closest_beacon)).or_insert('B');
+        }
+        for s in sensors {
+            marks.insert(self.block(s.pos), 'S');
+        }
+        let mut re = String::new();
+
+        for y in 0..height {
+            let row = self.cell(0, y).1;
+            let ranges = merged_ranges(sensors, row);
+            for x in 0..width {
+                let p = self.cell(x, y);
+                if let Some(mark) = marks.get(&Point(x, y)) {
+                    re.push(*mark);
+                } else if ranges.iter().any(|r| r.0 <= p.0 && p.0 <= r.1) {
+                    re.push('#');
+                } else {
+                    re.push('.');
+                }
+            }
+            re.push('\n');
+        }
+        re
+    }
+
+    // binary P6 image with translucent diamonds, uncovered blocks and the given holes in green
+    pub fn ppm(&self, sensors: &[Sensor], holes: &[Point]) -> Vec<u8> {
+        let (width, height) = self.size();
+        // large views have more pixels than i32 can count
+        let w = width as usize;
+        let mut pixels = vec![[16.0_f32, 16.0, 32.0]; w * height as usize];
+        let alpha = 0.3;
+
+        for y in 0..height {
+            let row = self.cell(0, y).1;
+            let line = &mut pixels[y as usize * w..(y as usize + 1) * w];
+            let mut covered = vec![false; w];
+
+            for (i, s) in sensors.iter().enumerate() {
+                if let Some((from, to)) = s.get_scanned_on_line(row) {
+                    // blocks whose top left cell is in range
+                    let first = (from - self.window.min.0 + self.scale - 1).div_euclid(self.scale);
+                    let last = (to - self.window.min.0).div_euclid(self.scale);
+                    for x in first.max(0)..=last.min(width - 1) {
+                        let color = PALETTE[i % PALETTE.len()];
+                        for c in 0..3 {
+                            line[x as usize][c] =
+                                line[x as usize][c] * (1.0 - alpha) + color[c] * alpha;
+                        }
+                        covered[x as usize] = true;
+                    }
+                }
+            }
+            for x in 0..w {
+                if !covered[x] {
+                    line[x] = [0.0, 255.0, 0.0];
+                }
+            }
+        }
+
+        // markers are 3 pixels wide, so they stay visible when scaled down
+        let mut mark = |p: Point, color: [f32; 3]| {
+            let b = self.block(p);
+            for m in b.neighbours8().chain([b]) {
+                if (0..width).contains(&m.0) && (0..height).contains(&m.1) {
+                    pixels[m.1 as usize * w + m.0 as usize] = color;
+                }
+            }
+        };
+        for s in sensors {
+            mark(s.pos, [255.0, 255.0, 255.0]);
+            mark(s.closest_beacon, [255.0, 40.0, 40.0]);
+        }
+        holes.iter().for_each(|h| mark(*h, [0.0, 255.0, 0.0]));
+
+        grid::ppm(
+            w,
+            height as usize,
+            pixels.iter().map(|p| p.map(|c| c as u8)),
+        )
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::testutil::Rng;
 
     const EXAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
@@ -155,7 +455,7 @@ Sensor at x=9, y=16: closest beacon is at x=10, y=16",
 
         assert_eq!(
             list,
-            vec![
+            Ok(vec![
                 Sensor {
                     pos: Point(2, 18),
                     closest_beacon: Point(-2, 15),
@@ -164,10 +464,19 @@ Sensor at x=9, y=16: closest beacon is at x=10, y=16",
                     pos: Point(9, 16),
                     closest_beacon: Point(10, 16)
                 }
-            ]
+            ])
         )
     }
 
+    #[test]
+    fn test_reader_error() {
+        let e = read("Sensor at x=2, y=18: closest beacon is at x=-2, y=15\nSensor at x=9, y=16")
+            .unwrap_err();
+
+        assert_eq!((e.line, e.column), (2, 1));
+        assert_eq!(e.msg, "expected a sensor");
+    }
+
     #[test]
     fn test_nonb_empty() {
         assert_eq!(get_num_non_becon_spaces(&Vec::new()[..], 0), 0);
@@ -195,15 +504,202 @@ Sensor at x=9, y=16: closest beacon is at x=10, y=16",
         assert_eq!(s.get_scanned_on_line(-6), None);
     }
 
+    #[test]
+    fn test_merged_ranges() {
+        let input = read(EXAMPLE).unwrap();
+
+        assert_eq!(merged_ranges(&input, 10), vec![(-2, 24)]);
+        assert_eq!(merged_ranges(&input, 11), vec![(-3, 13), (15, 25)]);
+        assert_eq!(merged_ranges(&input, 100), vec![]);
+    }
+
     #[test]
     fn part1_test() {
-        let input = &read(EXAMPLE)[..];
+        let input = &read(EXAMPLE).unwrap()[..];
         assert_eq!(get_num_non_becon_spaces(&input, 10), 26);
     }
 
     #[test]
     fn part2_test() {
-        let input = &read(EXAMPLE)[..];
-        assert_eq!(find_hole_in_square(&input, 20), 56000011);
+        let input = &read(EXAMPLE).unwrap()[..];
+        let square = BoundingBox::new(Point(0, 0), Point(20, 20));
+        assert_eq!(find_hole(&input, square), Some(Point(14, 11)));
+    }
+
+    #[test]
+    fn test_query_header() {
+        let input = format!("# row=10 region=0,0..20,20\n{}", EXAMPLE);
+        let report = read_report(&input).unwrap();
+
+        assert_eq!(report.query.row, 10);
+        assert_eq!(report.query.factor, 4_000_000);
+        assert_eq!(report.sensors.len(), 14);
+        assert_eq!(part1(&report), 26);
+        assert_eq!(part2(&report), 56000011);
+        assert_eq!(part2_perimeter(&report), 56000011);
+
+        let report = read_report(EXAMPLE).unwrap();
+        assert_eq!(report.query, BeaconQuery::default());
+    }
+
+    #[test]
+    fn test_query_header_error() {
+        let e = read_report("# row=10 size=20\n").unwrap_err();
+        assert_eq!((e.column, e.msg.as_str()), (10, "unknown setting"));
+
+        let e = read_report("# region=0,0..20\n").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (15, "20"));
+
+        let e = read_report(&format!("# factor=1\n{}\nSensor", EXAMPLE)).unwrap_err();
+        assert_eq!((e.line, e.msg.as_str()), (16, "expected a sensor"));
+    }
+
+    fn cells(holes: &[Gap]) -> Vec<Point> {
+        holes.iter().flat_map(|g| g.cells()).collect()
+    }
+
+    #[test]
+    fn test_find_holes() {
+        let input = read(EXAMPLE).unwrap();
+        let square = BoundingBox::new(Point(0, 0), Point(20, 20));
+
+        assert_eq!(
+            find_holes(&input, square),
+            vec![Gap {
+                y: 11,
+                from: 14,
+                to: 14
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_holes_ambiguous() {
+        // one small sensor leaves the corners of the region open
+        let sensors = vec![Sensor {
+            pos: Point(2, 2),
+            closest_beacon: Point(2, 0),
+        }];
+        let holes = cells(&find_holes(
+            &sensors,
+            BoundingBox::new(Point(0, 0), Point(4, 4)),
+        ));
+
+        assert_eq!(holes.len(), 12);
+        assert_eq!(
+            &holes[..4],
+            &[Point(0, 0), Point(1, 0), Point(3, 0), Point(4, 0)]
+        );
+        assert!(holes.iter().all(|p| p.man_dist(&Point(2, 2)) > 2));
+
+        // two separate areas, both are found
+        let holes = cells(&find_holes(
+            &sensors,
+            BoundingBox::new(Point(0, 0), Point(5, 2)),
+        ));
+        assert_eq!(holes.len(), 3 + 6);
+
+        // a diagonal line between two diamonds
+        let sensors = read(
+            "Sensor at x=0, y=0: closest beacon is at x=2, y=0
+Sensor at x=3, y=3: closest beacon is at x=5, y=3",
+        )
+        .unwrap();
+        let holes = cells(&find_holes(
+            &sensors,
+            BoundingBox::new(Point(0, 0), Point(3, 3)),
+        ));
+        assert_eq!(
+            holes,
+            vec![Point(3, 0), Point(2, 1), Point(1, 2), Point(0, 3)]
+        );
+    }
+
+    #[test]
+    fn test_find_holes_open() {
+        // without sensors the whole region is one gap per row
+        let region = BoundingBox::new(Point(-5, 0), Point(5, 999));
+        let holes = find_holes(&[], region);
+
+        assert_eq!(holes.len(), 1000);
+        assert!(holes.iter().all(|g| (g.from, g.to) == (-5, 5)));
+    }
+
+    #[test]
+    fn test_find_holes_brute_force() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..500 {
+            let sensors: Vec<Sensor> = (0..rng.between(1, 5))
+                .map(|_| {
+                    let pos = Point(rng.between(-2, 13), rng.between(-2, 13));
+                    Sensor {
+                        pos,
+                        closest_beacon: pos + Point(rng.between(-4, 4), rng.between(-4, 4)),
+                    }
+                })
+                .collect();
+            let min = Point(rng.between(0, 5), rng.between(0, 5));
+            let region = BoundingBox::new(min, min + Point(rng.between(0, 9), rng.between(0, 9)));
+
+            let expected: Vec<Point> = region
+                .points()
+                .filter(|p| sensors.iter().all(|s| s.pos.man_dist(p) > s.scan_range()))
+                .collect();
+            assert_eq!(
+                cells(&find_holes(&sensors, region)),
+                expected,
+                "{:?} in {:?}",
+                sensors,
+                region
+            );
+        }
+    }
+
+    #[test]
+    fn test_ascii() {
+        let input = read(EXAMPLE).unwrap();
+        let rows = |min: Point, max: Point| View::new(BoundingBox::new(min, max), 1).ascii(&input);
+
+        assert_eq!(
+            rows(Point(-2, 10), Point(24, 10)),
+            "####B######################\n"
+        );
+        assert_eq!(
+            rows(Point(-3, 11), Point(25, 11)),
+            "###S#############.###########\n"
+        );
+        assert_eq!(rows(Point(0, 0), Point(3, 1)), "##S#\n####\n");
+
+        // every other cell, marks show up in the block they fall in
+        let scaled = View::new(BoundingBox::new(Point(0, 0), Point(7, 3)), 2);
+        assert_eq!(scaled.ascii(&input), "#S##\n####\n");
+        let scaled = View::new(BoundingBox::new(Point(0, 0), Point(20, 20)), 3);
+        assert_eq!(
+            scaled.ascii(&input),
+            "S###S#S\n####SB#\n##S##S#\nS######\n####S#S\n###SS##\nS##S#S#\n"
+        );
+    }
+
+    #[test]
+    fn test_ppm() {
+        let input = read(EXAMPLE).unwrap();
+        let view = View::new(BoundingBox::new(Point(0, 0), Point(20, 20)), 1);
+        let img = view.ppm(&input, &[]);
+        let header = b"P6\n21 21\n255\n";
+
+        assert!(img.starts_with(header));
+        assert_eq!(img.len(), header.len() + 21 * 21 * 3);
+        let pixel = |x: usize, y: usize| {
+            let i = header.len() + (y * 21 + x) * 3;
+            img[i..i + 3].to_vec()
+        };
+        assert_eq!(pixel(14, 11), vec![0, 255, 0]);
+        assert_eq!(pixel(8, 7), vec![255, 255, 255]);
+        assert_ne!(pixel(5, 5), vec![0, 255, 0]);
+
+        // a larger scale keeps the markers
+        let small = View::new(BoundingBox::new(Point(0, 0), Point(20, 20)), 4).ppm(&input, &[]);
+        assert!(small.starts_with(b"P6\n6 6\n255\n"));
     }
 }
@@ -1,61 +1,217 @@
 use std::{
+    cmp::max,
     collections::{HashMap, HashSet},
     rc::Rc,
 };
 
 use Step::*;
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
-struct ValveId([char; 2]);
+use crate::{
+    parse::{parse_num, ParseError},
+    path::bfs,
+};
+
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Debug, Clone)]
+pub struct ValveId([char; 2]);
 
 impl ValveId {
-    fn new(code: &str) -> ValveId {
-        if code.len() != 2 {
-            panic!("Codes have to be length 2");
+    fn new(code: &str) -> Result<ValveId, ParseError> {
+        match code.chars().collect::<Vec<char>>()[..] {
+            [a, b] => Ok(ValveId([a, b])),
+            _ => Err(ParseError::new(16, code, code, "Codes have to be length 2")),
         }
+    }
 
-        let mut chars = code.chars();
+    fn start() -> ValveId {
+        ValveId(['A', 'A'])
+    }
 
-        ValveId([chars.next().unwrap(), chars.next().unwrap()])
+    pub fn code(&self) -> String {
+        self.0.iter().collect()
     }
 }
+
 impl std::fmt::Display for ValveId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}{})", self.0[0], self.0[1])
     }
 }
 
+// best_per_mask keeps one entry for every subset of these
+const MAX_VALVES: usize = 24;
+
 #[derive(Debug)]
-struct Map {
+pub struct Map {
     valves: HashMap<ValveId, Valve>,
 }
 
 impl Map {
-    fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let read_id = |code| ValveId::new(code).map_err(|e| e.within(input, code));
+
         let mut valves = HashMap::new();
+        let mut tunnels = Vec::new();
+        let mut rated = 0;
         for line in input.lines() {
             let mut words = line.split_whitespace().skip(1);
-            let valveid = ValveId::new(&words.next().unwrap());
-            let rate: usize = words
+            let valveid = read_id(
+                words
+                    .next()
+                    .ok_or_else(|| ParseError::missing(16, input, line, "valve"))?,
+            )?;
+            let rate_str = words
                 .nth(2)
-                .unwrap()
+                .ok_or_else(|| ParseError::missing(16, input, line, "rate"))?;
+            let rate: usize = match rate_str
                 .strip_prefix("rate=")
-                .unwrap()
-                .strip_suffix(";")
-                .unwrap()
-                .parse()
-                .unwrap();
+                .and_then(|r| r.strip_suffix(";"))
+            {
+                Some(r) => parse_num(16, input, r)?,
+                None => return Err(ParseError::new(16, input, rate_str, "expected rate=N;")),
+            };
+            if rate > 0 {
+                rated += 1;
+                if rated > MAX_VALVES {
+                    let msg = format!("more than {} valves with a flow rate", MAX_VALVES);
+                    return Err(ParseError::new(16, input, rate_str, &msg));
+                }
+            }
 
-            let connections: Vec<ValveId> = words
-                .skip(4)
-                .map(|s| s.strip_suffix(",").unwrap_or(s))
-                .map(|s| ValveId::new(s))
-                .collect();
+            let mut connections = Vec::new();
+            for code in words.skip(4).map(|s| s.strip_suffix(",").unwrap_or(s)) {
+                connections.push(read_id(code)?);
+                tunnels.push(code);
+            }
 
             valves.insert(valveid, Valve::new(rate, connections));
         }
 
-        Map { valves }
+        // every tunnel has to lead to a known valve
+        for code in tunnels {
+            if !valves.contains_key(&read_id(code)?) {
+                return Err(ParseError::new(16, input, code, "unknown valve"));
+            }
+        }
+
+        Ok(Map { valves })
+    }
+
+    fn distances_from(&self, from: &ValveId) -> HashMap<ValveId, usize> {
+        bfs(
+            [from.clone()],
+            |v| self.valves[v].paths_to.iter().cloned(),
+            |_| false,
+        )
+        .distances()
+        .clone()
+    }
+
+    // shortest tunnel route without from, ending at to
+    fn route(&self, from: &ValveId, to: &ValveId) -> Vec<ValveId> {
+        let mut route = bfs(
+            [from.clone()],
+            |v| self.valves[v].paths_to.iter().cloned(),
+            |v| v == to,
+        )
+        .path()
+        .expect("valve not reachable");
+        route.remove(0);
+        route
+    }
+
+    // only keeps the start and the reachable valves worth opening
+    fn compress(&self, start: &ValveId) -> ValveGraph {
+        let from_start = self.distances_from(start);
+        let mut ids = vec![start.clone()];
+        let mut useful: Vec<ValveId> = self
+            .valves
+            .iter()
+            .filter(|(id, v)| v.rate > 0 && *id != start && from_start.contains_key(id))
+            .map(|(id, _)| id.clone())
+            .collect();
+        useful.sort();
+        ids.append(&mut useful);
+
+        let rates = ids.iter().map(|id| self.valves[id].rate).collect();
+        let dist = ids
+            .iter()
+            .map(|from| {
+                let d = self.distances_from(from);
+                ids.iter()
+                    .map(|to| d.get(to).copied().unwrap_or(usize::MAX))
+                    .collect()
+            })
+            .collect();
+
+        ValveGraph { ids, rates, dist }
+    }
+
+    // graphviz dot graph of the tunnels, optionally highlighting the route of a plan
+    pub fn to_dot(&self, highlight: Option<&Plan>) -> String {
+        let mut route_edges = HashSet::new();
+        let mut opened = HashSet::new();
+        if let Some(plan) = highlight {
+            let mut pos = ValveId::start();
+            for (_, step) in &plan.steps {
+                match step {
+                    GoTo(next) => {
+                        route_edges.insert(edge(&pos, next));
+                        pos = next.clone();
+                    }
+                    Open(id) => {
+                        opened.insert(id.clone());
+                    }
+                }
+            }
+        }
+
+        let mut ids: Vec<&ValveId> = self.valves.keys().collect();
+        ids.sort();
+
+        let mut lines = vec!["graph tunnels {".to_string()];
+        for id in &ids {
+            let rate = self.valves[*id].rate;
+            let mut attrs = vec![format!("label=\"{}\\n{}\"", id.code(), rate)];
+            if rate > 0 {
+                attrs.push("shape=doublecircle".to_string());
+                attrs.push("style=filled".to_string());
+                attrs.push("fillcolor=lightblue".to_string());
+            }
+            if opened.contains(*id) {
+                attrs.push("color=red".to_string());
+                attrs.push("penwidth=2".to_string());
+            }
+            lines.push(format!("    {} [{}];", id.code(), attrs.join(", ")));
+        }
+
+        let mut edges: Vec<(ValveId, ValveId)> = self
+            .valves
+            .iter()
+            .flat_map(|(from, v)| v.paths_to.iter().map(move |to| edge(from, to)))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        edges.sort();
+        for (a, b) in edges {
+            let style = if route_edges.contains(&(a.clone(), b.clone())) {
+                " [color=red, penwidth=2]"
+            } else {
+                ""
+            };
+            lines.push(format!("    {} -- {}{};", a.code(), b.code(), style));
+        }
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+}
+
+// tunnels go both ways, so edges are stored in sorted order
+fn edge(a: &ValveId, b: &ValveId) -> (ValveId, ValveId) {
+    if a < b {
+        (a.clone(), b.clone())
+    } else {
+        (b.clone(), a.clone())
     }
 }
 
@@ -71,153 +227,328 @@ impl Valve {
     }
 }
 
+// start is always index 0, bit i of a mask stands for ids[i]
+#[derive(Debug)]
+struct ValveGraph {
+    ids: Vec<ValveId>,
+    rates: Vec<usize>,
+    dist: Vec<Vec<usize>>,
+}
+
+type Memo = HashMap<(usize, usize, u64), usize>;
+
+impl ValveGraph {
+    fn valve_mask(&self) -> u64 {
+        (0..self.ids.len())
+            .filter(|i| self.rates[*i] > 0)
+            .fold(0, |mask, i| mask | 1 << i)
+    }
+
+    // valves still closed that can be reached and opened in time, with the time left after opening
+    fn next_valves(
+        &self,
+        pos: usize,
+        time: usize,
+        mask: u64,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.ids.len())
+            .filter(move |next| self.rates[*next] > 0 && mask & (1 << next) == 0)
+            .filter(move |next| self.dist[pos][*next].saturating_add(1) < time)
+            .map(move |next| (next, time - self.dist[pos][next] - 1))
+    }
+
+    // most pressure still to be released from pos, ignoring all valves in mask
+    fn best_release(&self, pos: usize, time: usize, mask: u64, memo: &mut Memo) -> usize {
+        if let Some(re) = memo.get(&(pos, time, mask)) {
+            return *re;
+        }
+
+        let re = self
+            .next_valves(pos, time, mask)
+            .map(|(next, time_left)| {
+                self.rates[next] * time_left
+                    + self.best_release(next, time_left, mask | 1 << next, memo)
+            })
+            .max()
+            .unwrap_or(0);
+
+        memo.insert((pos, time, mask), re);
+        re
+    }
+
+    // follows the memo from the start to get the order the valves are opened in
+    fn best_order(&self, time: usize, mask: u64, memo: &mut Memo) -> Vec<ValveId> {
+        let mut order = Vec::new();
+        let (mut pos, mut time, mut mask) = (0, time, mask);
+
+        loop {
+            let target = self.best_release(pos, time, mask, memo);
+            if target == 0 {
+                return order;
+            }
+
+            let (next, time_left) = self
+                .next_valves(pos, time, mask)
+                .find(|(next, time_left)| {
+                    self.rates[*next] * time_left
+                        + self.best_release(*next, *time_left, mask | 1 << next, memo)
+                        == target
+                })
+                .unwrap();
+
+            order.push(self.ids[next].clone());
+            pos = next;
+            time = time_left;
+            mask |= 1 << next;
+        }
+    }
+
+    // position of a mask in best_per_mask, the start never has its bit set
+    fn slot(mask: u64) -> usize {
+        (mask >> 1) as usize
+    }
+
+    // best release of a single agent for every set of valves it opens
+    fn best_per_mask(&self, time: usize) -> Vec<usize> {
+        let mut best = vec![0; 1 << (self.ids.len() - 1)];
+        self.visit(0, time, 0, 0, &mut best);
+
+        // an agent allowed to open more valves does at least as well
+        for bit in 0..self.ids.len() - 1 {
+            for mask in 0..best.len() {
+                if mask & (1 << bit) != 0 {
+                    best[mask] = max(best[mask], best[mask ^ (1 << bit)]);
+                }
+            }
+        }
+        best
+    }
+
+    fn visit(&self, pos: usize, time: usize, mask: u64, relased: usize, best: &mut Vec<usize>) {
+        let idx = ValveGraph::slot(mask);
+        best[idx] = max(best[idx], relased);
+
+        for (next, time_left) in self.next_valves(pos, time, mask) {
+            let gain = self.rates[next] * time_left;
+            self.visit(next, time_left, mask | 1 << next, relased + gain, best);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
-struct Plan {
+pub struct Plan {
     map: Rc<Map>,
     pos: ValveId,
     open_valves: HashSet<ValveId>,
     relased: usize,
+    minute: usize,
+    // every step with the minute it is taken in, starting at minute 1
+    steps: Vec<(usize, Step)>,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
-enum Step {
+pub enum Step {
     GoTo(ValveId),
     Open(ValveId),
 }
 
 impl Plan {
+    pub fn relased(&self) -> usize {
+        self.relased
+    }
+
+    // every step with the minute it is taken in, starting at minute 1
+    pub fn steps(&self) -> &[(usize, Step)] {
+        &self.steps
+    }
+
     fn new(map: &Rc<Map>) -> Self {
         Self {
             map: Rc::clone(map),
-            pos: ValveId::new("AA"),
+            pos: ValveId::start(),
             open_valves: HashSet::new(),
             relased: 0,
+            minute: 0,
+            steps: Vec::new(),
         }
     }
 
-    fn is_open(&self, v_id: &ValveId) -> bool {
-        self.open_valves.contains(&v_id)
+    // walks to the valves in order and opens them, then waits until the time is up
+    fn from_order(map: &Rc<Map>, order: &[ValveId], max_steps: usize) -> Self {
+        let mut plan = Plan::new(map);
+        for valve in order {
+            for next in map.route(&plan.pos, valve) {
+                plan = plan.build_plan_with_step(GoTo(next));
+            }
+            plan = plan.build_plan_with_step(Open(valve.clone()));
+        }
+
+        for _ in plan.minute..max_steps {
+            plan.relase_one_min();
+        }
+        plan
     }
 
     fn build_plan_with_step(&self, step: Step) -> Plan {
         let mut re = (*self).clone();
         re.relase_one_min();
+        re.steps.push((re.minute, step.clone()));
         match step {
-            GoTo(id) => re.pos = id.clone(),
+            GoTo(id) => re.pos = id,
             Open(id) => {
-                re.open_valves.insert(id.clone());
+                re.open_valves.insert(id);
             }
         };
         re
     }
 
     fn relase_one_min(&mut self) {
+        self.minute += 1;
         for id in &self.open_valves {
-            let rate = self.map.valves.get(&id).unwrap().rate;
+            let rate = self.map.valves.get(id).unwrap().rate;
             self.relased += rate;
         }
     }
+}
 
-    fn upper_relase(&self, ticks: usize) -> usize {
-        let max_possible: usize = self.map.valves.values().map(|v| v.rate).sum::<usize>() * ticks;
-        max_possible + self.relased
+impl std::fmt::Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", report(&[(Agent::Me, self)]))
     }
+}
 
-    fn get_next_steps(&self) -> Vec<Step> {
-        let curr_pos = self.pos.clone();
-        let current_valve = self.map.valves.get(&curr_pos).unwrap();
-
-        let mut re = Vec::new();
-        // try to open valve
-        if current_valve.rate > 0 && !self.is_open(&curr_pos) {
-            re.push(Open(curr_pos.clone()))
-        }
+enum Agent {
+    Me,
+    Elephant,
+}
 
-        // continue in cave
-        for next_path in &current_valve.paths_to {
-            re.push(GoTo(next_path.clone()));
+impl Agent {
+    fn describe(&self, step: &Step) -> String {
+        match (self, step) {
+            (Agent::Me, GoTo(id)) => format!("You move to valve {}.", id.code()),
+            (Agent::Me, Open(id)) => format!("You open valve {}.", id.code()),
+            (Agent::Elephant, GoTo(id)) => format!("The elephant moves to valve {}.", id.code()),
+            (Agent::Elephant, Open(id)) => format!("The elephant opens valve {}.", id.code()),
         }
-        re
     }
 }
 
-fn find_best_plan(map: &Rc<Map>, max_steps: usize) -> Plan {
-    let mut pot_plans = vec![Plan::new(&Rc::clone(&map))];
-    let mut most_released = 0;
-
-    for i in 0..max_steps {
-        let mut next_plans = Vec::new();
-        // all plans take one step
-        for plan in &pot_plans {
-            for step in plan.get_next_steps() {
-                let plan_plus_step = plan.build_plan_with_step(step);
-                if plan_plus_step.upper_relase(max_steps - 1 - i) > most_released {
-                    if most_released <= plan_plus_step.relased {
-                        most_released = plan_plus_step.relased;
-                    }
-
-                    next_plans.push(plan_plus_step);
+// minute by minute listing like in the puzzle text
+fn report(agents: &[(Agent, &Plan)]) -> String {
+    let minutes = agents.iter().map(|(_, p)| p.minute).max().unwrap_or(0);
+    let mut open: Vec<(ValveId, usize)> = Vec::new();
+    let mut re = Vec::new();
+
+    for minute in 1..=minutes {
+        let mut lines = vec![format!("== Minute {} ==", minute)];
+
+        let mut codes: Vec<String> = open.iter().map(|(id, _)| id.code()).collect();
+        codes.sort();
+        let pressure: usize = open.iter().map(|(_, rate)| rate).sum();
+        lines.push(match codes.len() {
+            0 => "No valves are open.".to_string(),
+            1 => format!(
+                "Valve {} is open, releasing {} pressure.",
+                codes[0], pressure
+            ),
+            2 => format!(
+                "Valves {} and {} are open, releasing {} pressure.",
+                codes[0], codes[1], pressure
+            ),
+            n => format!(
+                "Valves {}, and {} are open, releasing {} pressure.",
+                codes[..n - 1].join(", "),
+                codes[n - 1],
+                pressure
+            ),
+        });
+
+        for (agent, plan) in agents {
+            for (_, step) in plan.steps.iter().filter(|(m, _)| *m == minute) {
+                lines.push(agent.describe(step));
+                if let Open(id) = step {
+                    open.push((id.clone(), plan.map.valves[id].rate));
                 }
             }
         }
-        println!("\nstep {},plans {}", i + 1, next_plans.len());
 
-        // only take the best paths at each position
-        let mut next_best_plans = Vec::new();
-        for v_id in map.valves.keys() {
-            let plan_with_this_pos = next_plans
-                .iter()
-                .filter(|p| p.pos == *v_id)
-                .cloned()
-                .collect::<Vec<Plan>>();
-
-            let mut checked_states = Vec::new();
-            for plan in &plan_with_this_pos {
-                if !checked_states.contains(&plan.open_valves) {
-                    next_best_plans.push(
-                        plan_with_this_pos
-                            .iter()
-                            .filter(|p| p.open_valves == plan.open_valves)
-                            .max_by_key(|p| p.relased)
-                            .cloned()
-                            .unwrap(),
-                    );
-
-                    checked_states.push(plan.open_valves.clone());
-                }
-            }
-        }
+        re.push(lines.join("\n"));
+    }
+
+    re.join("\n\n")
+}
+
+pub fn find_best_plan(map: &Rc<Map>, max_steps: usize) -> Plan {
+    let graph = map.compress(&ValveId::start());
+    let mut memo = HashMap::new();
+    let order = graph.best_order(max_steps, 0, &mut memo);
+    trace!(16, "{} valves, {} memo states", graph.ids.len(), memo.len());
 
-        //  next_plans.iter().filter(|p| p.relased > 0).for_each(|p| {
-        //      println!(
-        //          "[{}/{}]\t{:?}",
-        //          p.relased,
-        //          p.pos,
-        //          p.open_valves
-        //              .iter()
-        //              .map(|v| v.to_string())
-        //              .collect::<Vec<String>>()
-        //              .join(",")
-        //      )
-        //  });
+    let plan = Plan::from_order(map, &order, max_steps);
+    trace!(16, "best plan\n{}", plan);
+    plan
+}
+
+#[derive(Debug)]
+pub struct TeamPlan {
+    pub me: Plan,
+    pub elephant: Plan,
+}
+
+impl TeamPlan {
+    pub fn relased(&self) -> usize {
+        self.me.relased + self.elephant.relased
+    }
+}
 
-        pot_plans = next_best_plans;
+impl std::fmt::Display for TeamPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let agents = [(Agent::Me, &self.me), (Agent::Elephant, &self.elephant)];
+        write!(f, "{}", report(&agents))
     }
+}
 
-    pot_plans.into_iter().max_by_key(|p| p.relased).unwrap()
+// splits the valves between both agents so that the sum of their best plans is highest
+pub fn find_best_team_plan(map: &Rc<Map>, max_steps: usize) -> TeamPlan {
+    let graph = map.compress(&ValveId::start());
+    let best = graph.best_per_mask(max_steps);
+    let all = graph.valve_mask();
+
+    let my_valves = (0..=all)
+        .filter(|mask| mask & !all == 0)
+        .max_by_key(|mask| best[ValveGraph::slot(*mask)] + best[ValveGraph::slot(all ^ mask)])
+        .unwrap();
+
+    // each agent sees the valves of the other one as already open
+    let mut memo = HashMap::new();
+    let my_order = graph.best_order(max_steps, all ^ my_valves, &mut memo);
+    let elephant_order = graph.best_order(max_steps, my_valves, &mut memo);
+    trace!(
+        16,
+        "split {:b} of {:b}, {} memo states",
+        my_valves,
+        all,
+        memo.len()
+    );
+
+    TeamPlan {
+        me: Plan::from_order(map, &my_order, max_steps),
+        elephant: Plan::from_order(map, &elephant_order, max_steps),
+    }
 }
 
 #[aoc(day16, part1)]
-fn part1(input: &str) -> usize {
-    let map = Map::new(input);
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let map = Map::new(input)?;
     let best = find_best_plan(&Rc::new(map), 30);
     // 1488 to low
-    best.relased
+    Ok(best.relased)
 }
 
 #[aoc(day16, part2)]
-fn part2(input: &str) -> u32 {
-    todo!()
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let map = Map::new(input)?;
+    Ok(find_best_team_plan(&Rc::new(map), 26).relased())
 }
 
 #[cfg(test)]
@@ -237,29 +568,210 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
 
     #[test]
     fn test_build_map() {
-        let m = Map::new(EXAMPLE);
+        let m = Map::new(EXAMPLE).unwrap();
 
         assert_eq!(
-            m.valves.get(&ValveId::new("AA")),
+            m.valves.get(&ValveId::new("AA").unwrap()),
             Some(&Valve::new(
                 0,
-                vec![ValveId::new("DD"), ValveId::new("II"), ValveId::new("BB")]
+                vec![
+                    ValveId::new("DD").unwrap(),
+                    ValveId::new("II").unwrap(),
+                    ValveId::new("BB").unwrap()
+                ]
             ))
         );
         assert_eq!(
-            m.valves.get(&ValveId::new("HH")),
-            Some(&Valve::new(22, vec![ValveId::new("GG")]))
+            m.valves.get(&ValveId::new("HH").unwrap()),
+            Some(&Valve::new(22, vec![ValveId::new("GG").unwrap()]))
         );
     }
 
+    #[test]
+    fn test_build_map_error() {
+        let input = EXAMPLE.replace("rate=2;", "rate=x;");
+        let e = Map::new(&input).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 24, "x"));
+
+        let input = EXAMPLE.replace("valves AA, JJ", "valves AA, KK");
+        let e = Map::new(&input).unwrap_err();
+        assert_eq!((e.line, e.column, e.msg.as_str()), (9, 54, "unknown valve"));
+
+        let e = Map::new("Valve AAA has flow rate=0; tunnel leads to valve AA").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 7, "AAA"));
+
+        let many: Vec<String> = (0..25)
+            .map(|i| {
+                let code = format!("A{}", (b'A' + i) as char);
+                format!("Valve {} has flow rate=1; tunnel leads to valve AA", code)
+            })
+            .collect();
+        let e = Map::new(&many.join("\n")).unwrap_err();
+        assert_eq!((e.line, e.column), (25, 19));
+        assert_eq!(e.msg, "more than 24 valves with a flow rate");
+    }
+
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 1651)
+        assert_eq!(part1(EXAMPLE), Ok(1651))
     }
 
-    #[ignore = "Todo"]
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 70)
+        assert_eq!(part2(EXAMPLE), Ok(1707))
+    }
+
+    #[test]
+    fn test_compress() {
+        let g = Map::new(EXAMPLE)
+            .unwrap()
+            .compress(&ValveId::new("AA").unwrap());
+
+        assert_eq!(g.ids.len(), 7);
+        assert_eq!(g.ids[0], ValveId::new("AA").unwrap());
+        let idx = |code| {
+            g.ids
+                .iter()
+                .position(|id| *id == ValveId::new(code).unwrap())
+                .unwrap()
+        };
+        assert_eq!(g.dist[0][idx("DD")], 1);
+        assert_eq!(g.dist[0][idx("HH")], 5);
+        assert_eq!(g.dist[idx("JJ")][idx("HH")], 7);
+        assert_eq!(g.valve_mask(), 0b1111110);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let m = Map::new(
+            "Valve AA has flow rate=0; tunnels lead to valves BB, CC
+Valve BB has flow rate=13; tunnels lead to valves AA, CC
+Valve CC has flow rate=0; tunnels lead to valves AA, BB",
+        )
+        .unwrap();
+
+        assert_eq!(
+            m.to_dot(None),
+            r#"graph tunnels {
+    AA [label="AA\n0"];
+    BB [label="BB\n13", shape=doublecircle, style=filled, fillcolor=lightblue];
+    CC [label="CC\n0"];
+    AA -- BB;
+    AA -- CC;
+    BB -- CC;
+}"#
+        );
+    }
+
+    #[test]
+    fn test_to_dot_highlight() {
+        let map = Rc::new(Map::new(EXAMPLE).unwrap());
+        let best = find_best_plan(&map, 30);
+        let dot = map.to_dot(Some(&best));
+
+        assert!(dot.contains("    AA -- DD [color=red, penwidth=2];"));
+        assert!(dot.contains(
+            "    JJ [label=\"JJ\\n21\", shape=doublecircle, style=filled, fillcolor=lightblue, color=red, penwidth=2];"
+        ));
+    }
+
+    #[test]
+    fn test_route() {
+        let m = Map::new(EXAMPLE).unwrap();
+
+        assert_eq!(
+            m.route(&ValveId::new("BB").unwrap(), &ValveId::new("JJ").unwrap()),
+            vec![
+                ValveId::new("AA").unwrap(),
+                ValveId::new("II").unwrap(),
+                ValveId::new("JJ").unwrap()
+            ]
+        );
+        assert_eq!(
+            m.route(&ValveId::new("BB").unwrap(), &ValveId::new("BB").unwrap()),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_best_plan_steps() {
+        let best = find_best_plan(&Rc::new(Map::new(EXAMPLE).unwrap()), 30);
+
+        assert_eq!(best.relased, 1651);
+        assert_eq!(best.minute, 30);
+        assert_eq!(
+            best.steps.first(),
+            Some(&(1, GoTo(ValveId::new("DD").unwrap())))
+        );
+        assert_eq!(
+            best.steps.get(1),
+            Some(&(2, Open(ValveId::new("DD").unwrap())))
+        );
+        assert_eq!(
+            best.steps.last(),
+            Some(&(24, Open(ValveId::new("CC").unwrap())))
+        );
+    }
+
+    #[test]
+    fn test_plan_report() {
+        let report = find_best_plan(&Rc::new(Map::new(EXAMPLE).unwrap()), 30).to_string();
+
+        assert!(report.starts_with(
+            "== Minute 1 ==
+No valves are open.
+You move to valve DD.
+
+== Minute 2 ==
+No valves are open.
+You open valve DD.
+
+== Minute 3 ==
+Valve DD is open, releasing 20 pressure.
+You move to valve CC."
+        ));
+        assert!(report.contains(
+            "== Minute 6 ==
+Valves BB and DD are open, releasing 33 pressure.
+You move to valve AA."
+        ));
+        assert!(report.ends_with(
+            "== Minute 30 ==
+Valves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure."
+        ));
+    }
+
+    #[test]
+    fn test_team_report() {
+        let report = find_best_team_plan(&Rc::new(Map::new(EXAMPLE).unwrap()), 26).to_string();
+
+        assert!(report.contains("The elephant opens valve "));
+        assert!(report.ends_with(
+            "== Minute 26 ==
+Valves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure."
+        ));
+    }
+
+    #[test]
+    fn test_team_plan_splits_valves() {
+        let team = find_best_team_plan(&Rc::new(Map::new(EXAMPLE).unwrap()), 26);
+
+        assert_eq!(team.relased(), 1707);
+        assert_eq!(team.me.relased() + team.elephant.relased(), 1707);
+        assert!(team.me.steps().iter().all(|(m, _)| *m <= 26));
+        assert!(team.elephant.steps().iter().all(|(m, _)| *m <= 26));
+        assert!(team.me.open_valves.is_disjoint(&team.elephant.open_valves));
+        assert_eq!(
+            team.me.open_valves.len() + team.elephant.open_valves.len(),
+            6
+        );
+        for plan in [&team.me, &team.elephant] {
+            let opened = plan
+                .steps()
+                .iter()
+                .filter(|(_, s)| matches!(s, Open(_)))
+                .count();
+            assert_eq!(opened, plan.open_valves.len());
+        }
     }
 }
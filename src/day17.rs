@@ -1,26 +1,212 @@
+use std::collections::HashMap;
+
+use Jet::*;
+
+use crate::parse::ParseError;
+
+const WIDTH: usize = 7;
+
+// rows bottom to top, bit 6 is the left wall side, spawned two units from the left wall
+const ROCKS: [&[u8]; 5] = [
+    &[0b0011110],
+    &[0b0001000, 0b0011100, 0b0001000],
+    &[0b0011100, 0b0000100, 0b0000100],
+    &[0b0010000, 0b0010000, 0b0010000, 0b0010000],
+    &[0b0011000, 0b0011000],
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Jet {
+    Left,
+    Right,
+}
+
+#[aoc_generator(day17)]
+fn read(input: &str) -> Result<Vec<Jet>, ParseError> {
+    let line = input.trim();
+    if line.is_empty() {
+        return Err(ParseError::missing(17, input, line, "jets"));
+    }
+    line.char_indices()
+        .map(|(i, c)| match c {
+            '<' => Ok(Left),
+            '>' => Ok(Right),
+            _ => Err(ParseError::new(
+                17,
+                input,
+                &line[i..i + c.len_utf8()],
+                "unkown jet",
+            )),
+        })
+        .collect()
+}
+
+struct Chamber<'a> {
+    jets: &'a [Jet],
+    rows: Vec<u8>,
+    jet_idx: usize,
+    rock_count: usize,
+}
+
+impl<'a> Chamber<'a> {
+    fn new(jets: &'a [Jet]) -> Self {
+        Self {
+            jets,
+            rows: Vec::new(),
+            jet_idx: 0,
+            rock_count: 0,
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.rock_count % ROCKS.len()].to_vec();
+        let mut y = self.height() + 3;
+
+        loop {
+            let jet = self.jets[self.jet_idx];
+            self.jet_idx = (self.jet_idx + 1) % self.jets.len();
+
+            if let Some(pushed) = push(&rock, jet) {
+                if !self.collides(&pushed, y) {
+                    rock = pushed;
+                }
+            }
+
+            if y == 0 || self.collides(&rock, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        for (i, r) in rock.iter().enumerate() {
+            if y + i >= self.rows.len() {
+                self.rows.push(0);
+            }
+            self.rows[y + i] |= r;
+        }
+        self.rock_count += 1;
+    }
+
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter()
+            .enumerate()
+            .any(|(i, r)| self.rows.get(y + i).is_some_and(|row| row & r != 0))
+    }
+
+    // depth of the first blocked cell per column, counted from the top
+    fn surface(&self) -> [usize; WIDTH] {
+        let mut depths = [self.height(); WIDTH];
+        for (col, depth) in depths.iter_mut().enumerate() {
+            let mask = 1 << (WIDTH - 1 - col);
+            if let Some(d) = self.rows.iter().rev().position(|row| row & mask != 0) {
+                *depth = d;
+            }
+        }
+        depths
+    }
+
+    fn state(&self) -> (usize, usize, [usize; WIDTH]) {
+        (self.rock_count % ROCKS.len(), self.jet_idx, self.surface())
+    }
+}
+
+fn push(rock: &[u8], jet: Jet) -> Option<Vec<u8>> {
+    match jet {
+        Left if rock.iter().all(|r| r & (1 << (WIDTH - 1)) == 0) => {
+            Some(rock.iter().map(|r| r << 1).collect())
+        }
+        Right if rock.iter().all(|r| r & 1 == 0) => Some(rock.iter().map(|r| r >> 1).collect()),
+        _ => None,
+    }
+}
+
+fn simulate(jets: &[Jet], rocks: usize) -> usize {
+    let mut chamber = Chamber::new(jets);
+    while chamber.rock_count < rocks {
+        chamber.drop_rock();
+    }
+    chamber.height()
+}
+
+fn tower_height(jets: &[Jet], rocks: usize) -> usize {
+    let mut chamber = Chamber::new(jets);
+    let mut seen = HashMap::new();
+    let mut skipped_height = 0;
+
+    while chamber.rock_count < rocks {
+        chamber.drop_rock();
+
+        if skipped_height == 0 {
+            let now = (chamber.rock_count, chamber.height());
+            if let Some((prev_rocks, prev_height)) = seen.insert(chamber.state(), now) {
+                // same shape, jet and surface again: repeat the cycle as often as it fits
+                let cycle_len = chamber.rock_count - prev_rocks;
+                let cycles = (rocks - chamber.rock_count) / cycle_len;
+                skipped_height = cycles * (chamber.height() - prev_height);
+                chamber.rock_count += cycles * cycle_len;
+            }
+        }
+    }
+
+    chamber.height() + skipped_height
+}
+
 #[aoc(day17, part1)]
-fn part1(input: &str) -> u32 {
-    todo!()
+fn part1(input: &[Jet]) -> usize {
+    simulate(input, 2022)
 }
 
 #[aoc(day17, part2)]
-fn part2(input: &str) -> u32 {
-    todo!()
+fn part2(input: &[Jet]) -> usize {
+    tower_height(input, 1_000_000_000_000)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    const EXAMPLE: &str = "fillme";
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+    #[test]
+    fn test_reader() {
+        assert_eq!(read("<>><\n"), Ok(vec![Left, Right, Right, Left]));
+        assert_eq!(read("<>-<").unwrap_err().column, 3);
+        assert_eq!(read(" \n").unwrap_err().msg, "missing jets");
+    }
+
+    #[test]
+    fn test_first_rocks() {
+        let jets = read(EXAMPLE).unwrap();
+        let mut chamber = Chamber::new(&jets);
+        chamber.drop_rock();
+        assert_eq!(chamber.rows, vec![0b0011110]);
+        chamber.drop_rock();
+        assert_eq!(
+            chamber.rows,
+            vec![0b0011110, 0b0001000, 0b0011100, 0b0001000]
+        );
+        assert_eq!(chamber.surface(), [4, 4, 1, 0, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_cycle_matches_simulation() {
+        let jets = read(EXAMPLE).unwrap();
+        for rocks in [1, 10, 100, 2022, 5000] {
+            assert_eq!(tower_height(&jets, rocks), simulate(&jets, rocks));
+        }
+    }
 
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 157)
+        assert_eq!(part1(&read(EXAMPLE).unwrap()), 3068)
     }
 
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 70)
+        assert_eq!(part2(&read(EXAMPLE).unwrap()), 1514285714288)
     }
 }
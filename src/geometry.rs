@@ -0,0 +1,238 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use Direction::*;
+
+// x grows to the right, y grows downwards like in the puzzle drawings
+#[derive(Debug, Eq, Hash, PartialEq, Clone, Copy, PartialOrd, Ord)]
+pub struct Point(pub i32, pub i32);
+
+impl Point {
+    pub fn man_dist(&self, other: &Point) -> i32 {
+        (self.0 - other.0).abs() + (self.1 - other.1).abs()
+    }
+
+    // number of king moves between the points
+    pub fn chebyshev_dist(&self, other: &Point) -> i32 {
+        (self.0 - other.0).abs().max((self.1 - other.1).abs())
+    }
+
+    // each coordinate reduced to -1, 0 or 1
+    pub fn signum(&self) -> Point {
+        Point(self.0.signum(), self.1.signum())
+    }
+
+    // quarter turn around the origin, as seen on the screen
+    pub fn rotate_left(&self) -> Point {
+        Point(self.1, -self.0)
+    }
+
+    pub fn rotate_right(&self) -> Point {
+        Point(-self.1, self.0)
+    }
+
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |d| self + d.offset())
+    }
+
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Point(x, y)))
+            .filter(|p| *p != Point(0, 0))
+            .map(move |p| self + p)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+        self.1 += rhs.1;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+        self.1 -= rhs.1;
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        Point(-self.0, -self.1)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Point(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Direction {
+    U,
+    D,
+    L,
+    R,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [U, R, D, L];
+
+    pub fn offset(&self) -> Point {
+        match self {
+            U => Point(0, -1),
+            D => Point(0, 1),
+            L => Point(-1, 0),
+            R => Point(1, 0),
+        }
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            U => L,
+            L => D,
+            D => R,
+            R => U,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            U => R,
+            R => D,
+            D => L,
+            L => U,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.turn_left().turn_left()
+    }
+}
+
+// smallest axis aligned box containing some points, both corners are inside
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        let mut bb = BoundingBox::new(first, first);
+        points.for_each(|p| bb.extend(p));
+        Some(bb)
+    }
+
+    pub fn extend(&mut self, p: &Point) {
+        self.min = Point(self.min.0.min(p.0), self.min.1.min(p.1));
+        self.max = Point(self.max.0.max(p.0), self.max.1.max(p.1));
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        (self.min.0..=self.max.0).contains(&p.0) && (self.min.1..=self.max.1).contains(&p.1)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.max.0 - self.min.0 + 1
+    }
+
+    pub fn height(&self) -> i32 {
+        self.max.1 - self.min.1 + 1
+    }
+
+    // all points row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let bb = *self;
+        (bb.min.1..=bb.max.1).flat_map(move |y| (bb.min.0..=bb.max.0).map(move |x| Point(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ops() {
+        assert_eq!(Point(1, 2) + Point(3, -4), Point(4, -2));
+        assert_eq!(Point(1, 2) - Point(3, -4), Point(-2, 6));
+        assert_eq!(-Point(1, -2), Point(-1, 2));
+        assert_eq!(Point(1, -2) * 3, Point(3, -6));
+
+        let mut p = Point(0, 0);
+        p += Point(2, 2);
+        p -= Point(1, 0);
+        assert_eq!(p, Point(1, 2));
+    }
+
+    #[test]
+    fn test_dist() {
+        assert_eq!(Point(0, 0).man_dist(&Point(3, -4)), 7);
+        assert_eq!(Point(0, 0).chebyshev_dist(&Point(3, -4)), 4);
+        assert_eq!(Point(5, 5).chebyshev_dist(&Point(5, 5)), 0);
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(U.offset().rotate_right(), R.offset());
+        assert_eq!(U.offset().rotate_left(), L.offset());
+        for d in Direction::ALL {
+            assert_eq!(d.offset().rotate_right(), d.turn_right().offset());
+            assert_eq!(d.offset().rotate_left(), d.turn_left().offset());
+            assert_eq!(-d.offset(), d.opposite().offset());
+        }
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let n4: Vec<Point> = Point(0, 0).neighbours4().collect();
+        assert_eq!(
+            n4,
+            vec![Point(0, -1), Point(1, 0), Point(0, 1), Point(-1, 0)]
+        );
+
+        let n8: Vec<Point> = Point(1, 1).neighbours8().collect();
+        assert_eq!(n8.len(), 8);
+        assert!(n8.iter().all(|p| p.chebyshev_dist(&Point(1, 1)) == 1));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bb = BoundingBox::from_points(&[Point(2, 3), Point(-1, 5), Point(0, 4)]).unwrap();
+
+        assert_eq!(bb, BoundingBox::new(Point(-1, 3), Point(2, 5)));
+        assert_eq!((bb.width(), bb.height()), (4, 3));
+        assert!(bb.contains(&Point(0, 3)));
+        assert!(!bb.contains(&Point(3, 3)));
+        assert_eq!(bb.points().count(), 12);
+        assert_eq!(bb.points().next(), Some(Point(-1, 3)));
+        assert_eq!(BoundingBox::from_points(&[]), None);
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    geometry::{BoundingBox, Direction, Point},
+    parse::ParseError,
+};
+
+// rectangular map stored row by row, Point(0, 0) is the top left cell
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    // one cell per char, all lines need the same length
+    pub fn parse<F, E>(day: u32, input: &str, mut f: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, E>,
+        E: Display,
+    {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for line in input.lines() {
+            let mut len = 0;
+            for (i, c) in line.char_indices() {
+                let part = &line[i..i + c.len_utf8()];
+                let cell = f(c).map_err(|e| ParseError::new(day, input, part, &e.to_string()))?;
+                cells.push(cell);
+                len += 1;
+            }
+
+            match width {
+                None => width = Some(len),
+                Some(w) if w != len => {
+                    return Err(ParseError::new(day, input, line, "row length differs"))
+                }
+                _ => (),
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Point(0, 0),
+            Point(self.width as i32 - 1, self.height as i32 - 1),
+        )
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.0 >= 0 && p.1 >= 0 && (p.0 as usize) < self.width && (p.1 as usize) < self.height
+    }
+
+    fn idx(&self, p: Point) -> Option<usize> {
+        if self.contains(p) {
+            Some(p.1 as usize * self.width + p.0 as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.idx(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.idx(p).map(|i| &mut self.cells[i])
+    }
+
+    // all positions row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        self.bounds().points()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, t)| pred(t)).map(|(p, _)| p)
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.cells[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    // positions from start (included) in one direction until the edge
+    pub fn ray(&self, start: Point, dir: Direction) -> impl Iterator<Item = Point> + '_ {
+        let step = dir.offset();
+        std::iter::successors(Some(start), move |p| Some(*p + step))
+            .take_while(|p| self.contains(*p))
+    }
+
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours4().filter(|n| self.contains(*n))
+    }
+
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours8().filter(|n| self.contains(*n))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        self.get(p).expect("point outside of grid")
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        self.get_mut(p).expect("point outside of grid")
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(y) {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// binary P6 image, pixels are given row by row
+pub fn ppm(width: usize, height: usize, pixels: impl IntoIterator<Item = [u8; 3]>) -> Vec<u8> {
+    let mut img = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    img.extend(pixels.into_iter().flatten());
+    img
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Direction::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(0, input, |c| c.to_digit(10).ok_or("expected a digit"))
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456").unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Point(2, 1)), Some(&6));
+        assert_eq!(grid[Point(0, 1)], 4);
+        assert_eq!(grid.get(Point(3, 0)), None);
+        assert_eq!(grid.get(Point(0, -1)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let e = digits("123\n4x6").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 2));
+        assert_eq!(e.msg, "expected a digit");
+
+        let e = digits("123\n45").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+        assert_eq!(e.msg, "row length differs");
+    }
+
+    #[test]
+    fn test_rows_and_rays() {
+        let grid = digits("123\n456\n789").unwrap();
+
+        assert_eq!(grid.row(1).copied().collect::<Vec<_>>(), vec![4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![3, 6, 9]);
+        assert_eq!(
+            grid.ray(Point(1, 1), L).collect::<Vec<_>>(),
+            vec![Point(1, 1), Point(0, 1)]
+        );
+        assert_eq!(grid.ray(Point(0, 2), U).map(|p| grid[p]).sum::<u32>(), 12);
+        assert_eq!(grid.neighbours4(Point(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8(Point(1, 1)).count(), 8);
+        assert_eq!(grid.find(|c| *c == 8), Some(Point(1, 2)));
+    }
+
+    #[test]
+    fn test_map() {
+        let mut grid = Grid::new(2, 2, false);
+        grid[Point(1, 0)] = true;
+
+        assert_eq!(
+            grid.map(|b| if *b { '#' } else { '.' }).to_string(),
+            ".#\n.."
+        );
+    }
+
+    #[test]
+    fn test_ppm() {
+        let img = ppm(2, 1, [[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(img, b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06");
+    }
+}
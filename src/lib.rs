@@ -3,6 +3,15 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+#[macro_use]
+pub mod trace;
+pub mod geometry;
+pub mod grid;
+pub mod parse;
+pub mod path;
+#[cfg(test)]
+mod testutil;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -0,0 +1,130 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+// error of the input parsers, line and column are counted from 1
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub msg: String,
+    offset: usize,
+}
+
+impl ParseError {
+    // part has to be a slice of src, its position in src is reported
+    pub fn new(day: u32, src: &str, part: &str, msg: &str) -> Self {
+        ParseError::at(day, src, offset_in(src, part), part, msg)
+    }
+
+    // error for a line that ends before the expected part
+    pub fn missing(day: u32, src: &str, line: &str, what: &str) -> Self {
+        let offset = offset_in(src, line) + line.len();
+        ParseError::at(day, src, offset, line, &format!("missing {}", what))
+    }
+
+    // moves an error from parsing inner to its position in outer, inner has to be a slice of outer
+    pub fn within(self, outer: &str, inner: &str) -> Self {
+        let offset = offset_in(outer, inner) + self.offset;
+        ParseError::at(self.day, outer, offset, &self.text, &self.msg)
+    }
+
+    // byte position in the parsed text
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn at(day: u32, src: &str, offset: usize, text: &str, msg: &str) -> Self {
+        let before = &src[..offset.min(src.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            day,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.to_string(),
+            msg: msg.to_string(),
+            offset,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {} line {} column {}: {} \"{}\"",
+            self.day, self.line, self.column, self.msg, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+fn offset_in(src: &str, part: &str) -> usize {
+    let start = src.as_ptr() as usize;
+    let pos = part.as_ptr() as usize;
+    if pos >= start && pos + part.len() <= start + src.len() {
+        pos - start
+    } else {
+        src.find(part).unwrap_or(0)
+    }
+}
+
+// part has to be a slice of src
+pub fn parse_num<T: FromStr>(day: u32, src: &str, part: &str) -> Result<T, ParseError> {
+    part.trim()
+        .parse()
+        .map_err(|_| ParseError::new(day, src, part, "expected a number"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "R 4\nU x\nL 3";
+
+    #[test]
+    fn test_position() {
+        let line = INPUT.lines().nth(1).unwrap();
+        let e = ParseError::new(9, INPUT, &line[2..], "expected a number");
+
+        assert_eq!((e.line, e.column), (2, 3));
+        assert_eq!(e.text, "x");
+        assert_eq!(
+            e.to_string(),
+            "day 9 line 2 column 3: expected a number \"x\""
+        );
+    }
+
+    #[test]
+    fn test_missing() {
+        let line = INPUT.lines().nth(2).unwrap();
+        let e = ParseError::missing(9, INPUT, line, "length");
+
+        assert_eq!((e.line, e.column), (3, 4));
+        assert_eq!(e.msg, "missing length");
+        assert_eq!(e.text, "L 3");
+    }
+
+    #[test]
+    fn test_within() {
+        let line = INPUT.lines().nth(1).unwrap();
+        let inner = ParseError::new(9, line, &line[2..], "expected a number");
+        assert_eq!((inner.line, inner.column), (1, 3));
+
+        let e = inner.within(INPUT, line);
+        assert_eq!((e.line, e.column), (2, 3));
+    }
+
+    #[test]
+    fn test_parse_num() {
+        let line = INPUT.lines().nth(1).unwrap();
+
+        assert_eq!(parse_num::<i32>(9, INPUT, &INPUT[2..3]), Ok(4));
+        assert_eq!(
+            parse_num::<i32>(9, INPUT, &line[2..]).unwrap_err().column,
+            3
+        );
+    }
+}
@@ -0,0 +1,242 @@
+// Shortest paths over any graph given by a neighbour closure.
+// All searches start from every source at once and stop when a goal is taken from the queue,
+// a goal that never matches explores everything reachable.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+// distances and predecessors of all nodes seen by a search
+#[derive(Debug)]
+pub struct Search<N> {
+    dist: HashMap<N, usize>,
+    prev: HashMap<N, N>,
+    goal: Option<N>,
+}
+
+impl<N: Eq + Hash + Clone> Search<N> {
+    fn new() -> Self {
+        Self {
+            dist: HashMap::new(),
+            prev: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    pub fn dist(&self, node: &N) -> Option<usize> {
+        self.dist.get(node).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<N, usize> {
+        &self.dist
+    }
+
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    // nodes from one of the sources up to node, both included
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.dist.get(node)?;
+
+        let mut path = vec![node.clone()];
+        while let Some(before) = self.prev.get(path.last().unwrap()) {
+            path.push(before.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+pub fn bfs<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut q = VecDeque::new();
+    for s in sources {
+        if !search.dist.contains_key(&s) {
+            search.dist.insert(s.clone(), 0);
+            q.push_back(s);
+        }
+    }
+
+    while let Some(curr) = q.pop_front() {
+        if is_goal(&curr) {
+            search.goal = Some(curr);
+            break;
+        }
+
+        let d = search.dist[&curr];
+        for next in neighbours(&curr) {
+            if !search.dist.contains_key(&next) {
+                search.dist.insert(next.clone(), d + 1);
+                search.prev.insert(next.clone(), curr.clone());
+                q.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+pub fn dijkstra<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    astar(sources, neighbours, |_| 0, is_goal)
+}
+
+// the heuristic must never overestimate the remaining cost to a goal
+pub fn astar<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut search = Search::new();
+    // the heap only holds (estimate, cost, index into nodes), so N needs no Ord
+    let mut nodes = Vec::new();
+    let mut q = BinaryHeap::new();
+    for s in sources {
+        if !search.dist.contains_key(&s) {
+            search.dist.insert(s.clone(), 0);
+            q.push(Reverse((heuristic(&s), 0, nodes.len())));
+            nodes.push(s);
+        }
+    }
+
+    while let Some(Reverse((_, cost, idx))) = q.pop() {
+        let curr = nodes[idx].clone();
+        if cost > search.dist[&curr] {
+            // outdated entry, the node was reached cheaper since
+            continue;
+        }
+        if is_goal(&curr) {
+            search.goal = Some(curr);
+            break;
+        }
+
+        for (next, step) in neighbours(&curr) {
+            let new_cost = cost + step;
+            if search.dist.get(&next).is_none_or(|d| new_cost < *d) {
+                search.dist.insert(next.clone(), new_cost);
+                search.prev.insert(next.clone(), curr.clone());
+                q.push(Reverse((
+                    new_cost + heuristic(&next),
+                    new_cost,
+                    nodes.len(),
+                )));
+                nodes.push(next);
+            }
+        }
+    }
+
+    search
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{geometry::Point, grid::Grid};
+
+    const MAZE: &str = "S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E";
+
+    fn maze() -> Grid<char> {
+        Grid::parse(0, MAZE, Ok::<char, &str>).unwrap()
+    }
+
+    fn open(grid: &Grid<char>, p: &Point) -> Vec<Point> {
+        grid.neighbours4(*p).filter(|n| grid[*n] != '#').collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let start = grid.find(|c| *c == 'S').unwrap();
+        let search = bfs([start], |p| open(&grid, p), |p| grid[*p] == 'E');
+        let path = search.path().unwrap();
+
+        assert_eq!(search.goal(), Some(&Point(7, 4)));
+        assert_eq!(path.len(), 16);
+        assert_eq!(path.first(), Some(&start));
+        assert!(path.windows(2).all(|w| w[0].man_dist(&w[1]) == 1));
+    }
+
+    #[test]
+    fn test_bfs_all_reachable() {
+        let grid = maze();
+        let search = bfs([Point(0, 0)], |p| open(&grid, p), |_| false);
+
+        assert_eq!(search.goal(), None);
+        assert_eq!(search.path(), None);
+        assert_eq!(search.dist(&Point(7, 4)), Some(15));
+        assert_eq!(search.dist(&Point(3, 0)), None);
+        assert_eq!(search.distances().len(), 27);
+    }
+
+    #[test]
+    fn test_multi_source() {
+        let grid = maze();
+        let search = bfs(
+            [Point(0, 0), Point(7, 0)],
+            |p| open(&grid, p),
+            |p| *p == Point(4, 0),
+        );
+
+        assert_eq!(search.dist(&Point(4, 0)), Some(3));
+        assert_eq!(search.path().unwrap().first(), Some(&Point(7, 0)));
+    }
+
+    #[test]
+    fn test_weighted() {
+        // going through the 9 is shorter but more expensive
+        let grid: Grid<u32> = Grid::parse(0, "1111\n1911\n1111", |c| {
+            c.to_digit(10).ok_or("expected a digit")
+        })
+        .unwrap();
+        let weighted = |p: &Point| -> Vec<(Point, usize)> {
+            grid.neighbours4(*p)
+                .map(|n| (n, grid[n] as usize))
+                .collect()
+        };
+        let goal = Point(1, 2);
+
+        let d = dijkstra([Point(1, 0)], weighted, |p| *p == goal);
+        let a = astar(
+            [Point(1, 0)],
+            weighted,
+            |p| p.man_dist(&goal) as usize,
+            |p| *p == goal,
+        );
+
+        assert_eq!(d.dist(&goal), Some(4));
+        assert_eq!(a.dist(&goal), Some(4));
+        assert!(!d.path().unwrap().contains(&Point(1, 1)));
+        assert_eq!(a.path().unwrap().len(), 5);
+    }
+}
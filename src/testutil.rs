@@ -0,0 +1,18 @@
+// Helpers shared by the tests of several days.
+
+// xorshift, enough to get reproducible random inputs without another crate
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+
+    // both ends included
+    pub fn between(&mut self, min: i32, max: i32) -> i32 {
+        min + self.below((max - min + 1) as u64) as i32
+    }
+}
@@ -0,0 +1,88 @@
+// Debug output of the solvers, silent unless switched on for a day.
+// enable(day) switches it on from code, the runner reads AOC_TRACE, e.g. AOC_TRACE=13,16 or AOC_TRACE=all.
+// Events go to stderr, so the answers on stdout stay clean.
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Once,
+};
+
+static DAYS: AtomicU32 = AtomicU32::new(0);
+static FROM_ENV: Once = Once::new();
+
+fn days() -> &'static AtomicU32 {
+    FROM_ENV.call_once(|| {
+        if let Ok(val) = std::env::var("AOC_TRACE") {
+            DAYS.fetch_or(parse_days(&val), Ordering::Relaxed);
+        }
+    });
+    &DAYS
+}
+
+fn parse_days(val: &str) -> u32 {
+    if val.trim() == "all" {
+        return u32::MAX;
+    }
+
+    val.split(',')
+        .filter_map(|d| d.trim().parse::<u32>().ok())
+        .fold(0, |mask, d| mask | bit(d))
+}
+
+// days outside of 1..=25 have no bit and are never traced
+fn bit(day: u32) -> u32 {
+    if (1..=25).contains(&day) {
+        1 << day
+    } else {
+        0
+    }
+}
+
+pub fn enable(day: u32) {
+    days().fetch_or(bit(day), Ordering::Relaxed);
+}
+
+pub fn disable(day: u32) {
+    days().fetch_and(!bit(day), Ordering::Relaxed);
+}
+
+pub fn is_enabled(day: u32) -> bool {
+    days().load(Ordering::Relaxed) & bit(day) != 0
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($day:expr, $($arg:tt)*) => {
+        if $crate::trace::is_enabled($day) {
+            eprintln!("[day{:02}] {}", $day, format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days(""), 0);
+        assert_eq!(parse_days("13"), 1 << 13);
+        assert_eq!(parse_days("1, 16,x,30"), 1 << 1 | 1 << 16);
+        assert_eq!(parse_days("all"), u32::MAX);
+    }
+
+    #[test]
+    fn test_enable() {
+        // day 25 is not traced by any other test
+        enable(25);
+        assert!(is_enabled(25));
+        disable(25);
+        assert!(!is_enabled(25));
+
+        for day in [0, 26, 32, 100] {
+            enable(day);
+            assert!(!is_enabled(day));
+            disable(day);
+        }
+    }
+}